@@ -0,0 +1,74 @@
+pub struct Args
+{
+    pub(crate) seed: Option<u64>,
+    pub(crate) record: Option<String>,
+    pub(crate) replay: Option<String>,
+    pub(crate) verify: Option<String>,
+    pub(crate) autopilot: bool,
+    pub(crate) campaign: Option<String>,
+    pub(crate) log_events: bool,
+}
+
+impl Args
+{
+    pub fn parse() -> Result<Args, String>
+    {
+        let mut args = Args
+        {
+            seed: None,
+            record: None,
+            replay: None,
+            verify: None,
+            autopilot: false,
+            campaign: None,
+            log_events: false,
+        };
+
+        let mut words = std::env::args().skip(1);
+        while let Some(word) = words.next()
+        {
+            match word.as_str()
+            {
+                "--seed" =>
+                {
+                    let value = Self::get_value(&word, words.next())?;
+                    let seed = match value.parse()
+                    {
+                        Ok(s) => s,
+                        Err(_) => return Err(format!("Bad seed: {value}")),
+                    };
+                    args.seed = Some(seed);
+                }
+                "--record" => args.record = Some(Self::get_value(&word, words.next())?),
+                "--replay" => args.replay = Some(Self::get_value(&word, words.next())?),
+                "--verify" => args.verify = Some(Self::get_value(&word, words.next())?),
+                "--autopilot" => args.autopilot = true,
+                "--log-events" => args.log_events = true,
+                "--campaign" => args.campaign = Some(Self::get_value(&word, words.next())?),
+                _ => return Err(format!("Unknown argument: {word}")),
+            }
+        }
+
+        if args.record.is_some() && args.replay.is_some()
+        {
+            return Err("Cannot record while playing a replay back.".to_string());
+        }
+
+        // A replay is played with the seed and config it was recorded with.
+        if (args.seed.is_some() || args.autopilot) && (args.replay.is_some() || args.verify.is_some())
+        {
+            return Err("--seed and --autopilot cannot be used with --replay or --verify.".to_string());
+        }
+
+        Ok(args)
+    }
+
+    fn get_value(flag: &str, value: Option<String>) -> Result<String, String>
+    {
+        match value
+        {
+            Some(v) => Ok(v),
+            None => Err(format!("Missing value for {flag}")),
+        }
+    }
+}
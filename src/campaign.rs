@@ -0,0 +1,216 @@
+use std::fs::File;
+use std::io::{ErrorKind, Read, Write};
+use serde::{Deserialize, Serialize};
+use crate::config::Config;
+use crate::game::{FoodKind, Goal};
+
+/// One level of a campaign, played on top of the regular config.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CampaignLevel
+{
+    pub(crate) name: String,
+    /// Level file with the map, or none for an open board.
+    #[serde(default)]
+    pub(crate) map: Option<String>,
+    pub(crate) goal: Goal,
+    /// Ticks per second at the start of the level.
+    pub(crate) speed: f64,
+    /// Food types that appear on the level, spawning at least once in a while even if the
+    /// config has them switched off. Leave out for the food of the config.
+    #[serde(default)]
+    pub(crate) foods: Option<Vec<FoodKind>>,
+}
+
+/// Which level of the campaign the player has got to, kept between sessions.
+#[derive(Serialize, Deserialize, Default)]
+struct Progress
+{
+    level: usize,
+}
+
+/// The campaign file.
+#[derive(Serialize, Deserialize)]
+struct CampaignFile
+{
+    levels: Vec<CampaignLevel>,
+}
+
+/// A sequence of levels played one after another, each cleared by reaching its goal.
+pub struct Campaign
+{
+    levels: Vec<CampaignLevel>,
+    /// Config the levels are built on.
+    base_config: Config,
+    level: usize,
+}
+
+impl Campaign
+{
+    /// Loads the campaign from `filename` and picks up at the level saved in the progress file.
+    pub fn load(filename: &str, config: &Config) -> Result<Campaign, String>
+    {
+        let mut file = match File::open(filename)
+        {
+            Ok(f) => f,
+            Err(e) => return Err(format!("Could not open campaign {filename}: {e}")),
+        };
+        let mut content = String::new();
+
+        match file.read_to_string(&mut content)
+        {
+            Ok(_) => {},
+            Err(_) => return Err(format!("Could not read campaign {filename}")),
+        }
+
+        let levels = match serde_json::from_str::<CampaignFile>(&content)
+        {
+            Ok(c) => c.levels,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        if levels.is_empty()
+        {
+            return Err("The campaign has no levels.".to_string());
+        }
+
+        let mut campaign = Campaign { levels, base_config: config.clone(), level: 0 };
+        campaign.level = campaign.load_progress()?.level.min(campaign.levels.len() - 1);
+
+        Ok(campaign)
+    }
+
+    fn load_progress(&self) -> Result<Progress, String>
+    {
+        let mut file = match File::open(&self.base_config.campaign_progress_file)
+        {
+            Ok(f) => f,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Progress::default()),
+            Err(e) => return Err(e.to_string()),
+        };
+        let mut content = String::new();
+
+        match file.read_to_string(&mut content)
+        {
+            Ok(_) => {},
+            Err(_) => return Err("Could not read the campaign progress".to_string()),
+        }
+
+        match serde_json::from_str(&content)
+        {
+            Ok(p) => Ok(p),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn save_progress(&self) -> Result<(), String>
+    {
+        let content = match serde_json::to_string(&Progress { level: self.level })
+        {
+            Ok(c) => c,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let mut file = match File::create(&self.base_config.campaign_progress_file)
+        {
+            Ok(f) => f,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        match file.write_all(content.as_bytes())
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Index of the level being played, counting from zero.
+    pub(crate) fn get_level_index(&self) -> usize
+    {
+        self.level
+    }
+
+    pub(crate) fn get_level(&self) -> &CampaignLevel
+    {
+        &self.levels[self.level]
+    }
+
+    /// Moves on to the next level and saves the progress. Once the last level is cleared
+    /// the campaign starts over, and `false` is returned.
+    pub(crate) fn advance(&mut self) -> Result<bool, String>
+    {
+        self.level += 1;
+        let has_next = self.level < self.levels.len();
+        if !has_next
+        {
+            self.level = 0;
+        }
+
+        self.save_progress()?;
+        Ok(has_next)
+    }
+
+    /// The config the current level is played with.
+    pub(crate) fn make_config(&self) -> Config
+    {
+        let level = self.get_level();
+        let mut config = self.base_config.clone();
+
+        config.level = level.map.clone();
+        config.speed.start = level.speed;
+        config.goal = Some(level.goal);
+
+        if let Some(foods) = &level.foods
+        {
+            for kind in [FoodKind::Regular, FoodKind::Bonus, FoodKind::Shrinking, FoodKind::Poison]
+            {
+                let food_type = config.food.get_type_mut(kind);
+                food_type.weight = match foods.contains(&kind)
+                {
+                    true => food_type.weight.max(1),
+                    false => 0,
+                };
+            }
+        }
+
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn campaign_with_foods(foods: Option<Vec<FoodKind>>) -> Campaign
+    {
+        let mut base_config = Config::for_board(8, 8);
+        base_config.food.regular.weight = 5;
+        base_config.food.bonus.weight = 3;
+
+        let level = CampaignLevel { name: "test".to_string(), map: None, goal: Goal::Points(10),
+                                    speed: 8.0, foods };
+        Campaign { levels: vec![level], base_config, level: 0 }
+    }
+
+    fn weights(config: &Config) -> Vec<u32>
+    {
+        [FoodKind::Regular, FoodKind::Bonus, FoodKind::Shrinking, FoodKind::Poison].iter()
+            .map(|kind| config.food.get_type(*kind).weight)
+            .collect()
+    }
+
+    #[test]
+    fn level_foods_switch_the_other_foods_off_and_their_own_on()
+    {
+        let campaign = campaign_with_foods(Some(vec![FoodKind::Regular, FoodKind::Shrinking]));
+        assert_eq!(weights(&campaign.make_config()), vec![5, 0, 1, 0]);
+    }
+
+    #[test]
+    fn levels_without_foods_keep_the_food_of_the_config()
+    {
+        let config = campaign_with_foods(None).make_config();
+        assert_eq!(weights(&config), vec![5, 3, 0, 0]);
+        assert_eq!(config.speed.start, 8.0);
+    }
+}
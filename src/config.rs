@@ -1,9 +1,17 @@
 use std::fs::File;
 use std::io::Read;
 use serde::{Deserialize, Serialize};
-use crate::game::Color;
+use crate::display::sound::SoundEffectsConfig;
+use crate::game::{Color, Difficulty, FoodConfig, Goal, Mode, PowerUpConfig, SpeedConfig, Topology};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerColors
+{
+    pub(crate) head_color: Color,
+    pub(crate) snake_color: Color,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Config
 {
     pub(crate) head_color: Color,
@@ -12,7 +20,100 @@ pub struct Config
     pub(crate) screen_width: i64,
     pub(crate) screen_height: i64,
     pub(crate) cell_size: i64,
+    /// Board size in cells. Left out, the board fills the screen with cells of `cell_size` pixels.
+    #[serde(default)]
+    pub(crate) board_width: Option<i64>,
+    #[serde(default)]
+    pub(crate) board_height: Option<i64>,
     pub(crate) initial_cell_count: i64,
+    #[serde(default)]
+    pub(crate) topology: Topology,
+    #[serde(default)]
+    pub(crate) seed: Option<u64>,
+    #[serde(default)]
+    pub(crate) level: Option<String>,
+    #[serde(default = "default_wall_color")]
+    pub(crate) wall_color: Color,
+    #[serde(default)]
+    pub(crate) food: FoodConfig,
+    #[serde(default)]
+    pub(crate) speed: SpeedConfig,
+    #[serde(default)]
+    pub(crate) power_ups: PowerUpConfig,
+    /// Rules the game is played by.
+    #[serde(default)]
+    pub(crate) mode: Mode,
+    /// Ends the game as won once it is met. Leave out for an endless game.
+    #[serde(default)]
+    pub(crate) goal: Option<Goal>,
+    /// Colours of the second snake, steered with WASD. Leave out for a single player game.
+    #[serde(default)]
+    pub(crate) second_player: Option<PlayerColors>,
+    #[serde(default = "default_lives")]
+    pub(crate) lives: u32,
+    /// Computer-controlled snakes sharing the board.
+    #[serde(default)]
+    pub(crate) opponents: Vec<Difficulty>,
+    /// Lets the autopilot steer the first player's snake from the start.
+    #[serde(default)]
+    pub(crate) autopilot: bool,
+    /// Directory the background music is played from, in random order.
+    #[serde(default = "default_music_path")]
+    pub(crate) music_path: String,
+    /// Volume the music starts at, 1 playing it as recorded and 2 at most.
+    #[serde(default = "default_music_volume")]
+    pub(crate) music_volume: f32,
+    /// Clips played when things happen in the game.
+    #[serde(default)]
+    pub(crate) sound_effects: SoundEffectsConfig,
+    /// Where the high score table of the classic mode is kept. The other modes keep theirs
+    /// next to it, with the mode added to the name.
+    #[serde(default = "default_high_score_file")]
+    pub(crate) high_score_file: String,
+    /// How many runs the high score table keeps.
+    #[serde(default = "default_high_score_count")]
+    pub(crate) high_score_count: usize,
+    /// Campaign file to play through level by level. Leave out for a single endless game.
+    #[serde(default)]
+    pub(crate) campaign: Option<String>,
+    /// Where the level reached in the campaign is kept.
+    #[serde(default = "default_campaign_progress_file")]
+    pub(crate) campaign_progress_file: String,
+}
+
+fn default_wall_color() -> Color
+{
+    (128, 128, 128)
+}
+
+fn default_lives() -> u32
+{
+    1
+}
+
+fn default_music_path() -> String
+{
+    "assets/music".to_string()
+}
+
+fn default_music_volume() -> f32
+{
+    1.0
+}
+
+fn default_high_score_file() -> String
+{
+    "highscores.json".to_string()
+}
+
+fn default_high_score_count() -> usize
+{
+    10
+}
+
+fn default_campaign_progress_file() -> String
+{
+    "campaign_progress.json".to_string()
 }
 
 impl Config
@@ -41,4 +142,51 @@ impl Config
         Ok(config)
     }
 
-}
\ No newline at end of file
+    /// Where the high score table of the configured mode is kept.
+    pub(crate) fn get_high_score_file(&self) -> String
+    {
+        if self.mode == Mode::Classic
+        {
+            return self.high_score_file.clone();
+        }
+
+        let mode = self.mode.get_name();
+        match self.high_score_file.rsplit_once('.')
+        {
+            Some((stem, extension)) => format!("{stem}_{mode}.{extension}"),
+            None => format!("{}_{mode}", self.high_score_file),
+        }
+    }
+
+    /// Columns and rows of the board.
+    pub(crate) fn get_board_size(&self) -> (i64, i64)
+    {
+        let cells_across = |pixels: i64| pixels.checked_div(self.cell_size).unwrap_or(0);
+
+        (self.board_width.unwrap_or_else(|| cells_across(self.screen_width)),
+         self.board_height.unwrap_or_else(|| cells_across(self.screen_height)))
+    }
+
+}
+
+#[cfg(test)]
+impl Config
+{
+    /// A single player classic game on a `width` by `height` board, for tests.
+    pub(crate) fn for_board(width: i64, height: i64) -> Config
+    {
+        let json = format!(r#"{{
+            "head_color": [255, 255, 0],
+            "snake_color": [0, 255, 0],
+            "background_color": [0, 0, 128],
+            "screen_width": 400,
+            "screen_height": 400,
+            "cell_size": 10,
+            "board_width": {width},
+            "board_height": {height},
+            "initial_cell_count": 3
+        }}"#);
+
+        serde_json::from_str(&json).unwrap()
+    }
+}
@@ -0,0 +1,198 @@
+use std::time::{Duration, Instant};
+
+/// Source of time for the game loop, so the loop can be driven without waiting in real time.
+pub trait Clock
+{
+    /// Time passed since the clock was created.
+    fn now(&self) -> Duration;
+    fn sleep(&mut self, duration: Duration);
+}
+
+/// The wall clock.
+pub struct SystemClock
+{
+    start: Instant,
+}
+
+impl SystemClock
+{
+    pub fn new() -> SystemClock
+    {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Clock for SystemClock
+{
+    fn now(&self) -> Duration
+    {
+        self.start.elapsed()
+    }
+
+    fn sleep(&mut self, duration: Duration)
+    {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Time between two rendered frames, about 60 per second.
+pub(crate) const FRAME_INTERVAL: Duration = Duration::from_micros(16_667);
+/// Most game ticks run in one frame to catch up after a slow one. Past that the game
+/// falls behind rather than racing ahead.
+const MAX_TICKS_PER_FRAME: u32 = 5;
+
+/// Schedules game ticks at the game's speed, whatever the frame rate.
+pub(crate) struct Ticker
+{
+    /// When the next game tick is due, on the clock.
+    next_tick: Duration,
+    /// Ticks run since the ticker last caught up.
+    ticks_behind: u32,
+}
+
+impl Ticker
+{
+    pub(crate) fn new() -> Ticker
+    {
+        Ticker { next_tick: Duration::ZERO, ticks_behind: 0 }
+    }
+
+    /// Makes the next tick due at `now`, for a game that starts or goes on after a wait.
+    pub(crate) fn restart(&mut self, now: Duration)
+    {
+        self.next_tick = now;
+        self.ticks_behind = 0;
+    }
+
+    /// Whether a tick is due by `now`. After too many ticks in a row the ticker gives up
+    /// catching up and counts from `now` instead.
+    pub(crate) fn is_due(&mut self, now: Duration) -> bool
+    {
+        if self.next_tick > now
+        {
+            self.ticks_behind = 0;
+            return false;
+        }
+
+        if self.ticks_behind == MAX_TICKS_PER_FRAME
+        {
+            self.restart(now);
+            return false;
+        }
+
+        true
+    }
+
+    /// Counts a tick as run, with the next one due `interval` after it.
+    pub(crate) fn tick(&mut self, interval: Duration)
+    {
+        self.next_tick += interval;
+        self.ticks_behind += 1;
+    }
+
+    /// Sleeps until the next frame, or the next tick if that comes first and `is_ticking`.
+    pub(crate) fn wait_for_next_frame(&self, clock: &mut dyn Clock, frame_start: Duration, is_ticking: bool)
+    {
+        let wake_up = match is_ticking
+        {
+            true => (frame_start + FRAME_INTERVAL).min(self.next_tick),
+            false => frame_start + FRAME_INTERVAL,
+        };
+
+        let now = clock.now();
+        if wake_up > now
+        {
+            clock.sleep(wake_up - now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// A clock that only moves when slept on or pushed on by hand.
+    struct FakeClock
+    {
+        now: Duration,
+    }
+
+    impl Clock for FakeClock
+    {
+        fn now(&self) -> Duration
+        {
+            self.now
+        }
+
+        fn sleep(&mut self, duration: Duration)
+        {
+            self.now += duration;
+        }
+    }
+
+    /// Runs the ticks due by now, as one frame of the game loop does, and returns how many.
+    fn run_frame(ticker: &mut Ticker, clock: &mut FakeClock, interval: Duration) -> u32
+    {
+        let frame_start = clock.now();
+        let mut ticks = 0;
+        while ticker.is_due(frame_start)
+        {
+            ticker.tick(interval);
+            ticks += 1;
+        }
+        ticker.wait_for_next_frame(clock, frame_start, true);
+        ticks
+    }
+
+    #[test]
+    fn ticks_at_the_game_speed_whatever_the_frame_rate()
+    {
+        let mut clock = FakeClock { now: Duration::ZERO };
+        let mut ticker = Ticker::new();
+        let interval = Duration::from_millis(100);
+
+        let mut ticks = 0;
+        while clock.now() < Duration::from_secs(2)
+        {
+            ticks += run_frame(&mut ticker, &mut clock, interval);
+        }
+        assert_eq!(ticks, 20);
+    }
+
+    #[test]
+    fn wakes_up_for_a_tick_due_before_the_next_frame()
+    {
+        let mut clock = FakeClock { now: Duration::ZERO };
+        let mut ticker = Ticker::new();
+        let interval = Duration::from_millis(5);
+
+        run_frame(&mut ticker, &mut clock, interval);
+        assert_eq!(clock.now(), interval);
+    }
+
+    #[test]
+    fn gives_up_catching_up_after_a_slow_frame()
+    {
+        let mut clock = FakeClock { now: Duration::ZERO };
+        let mut ticker = Ticker::new();
+        let interval = Duration::from_millis(10);
+
+        clock.sleep(Duration::from_secs(1));
+        assert_eq!(run_frame(&mut ticker, &mut clock, interval), MAX_TICKS_PER_FRAME);
+        assert_eq!(run_frame(&mut ticker, &mut clock, interval), 1);
+    }
+
+    #[test]
+    fn only_waits_for_the_frame_while_not_ticking()
+    {
+        let start = Duration::from_secs(1);
+        let mut clock = FakeClock { now: start };
+        let ticker = Ticker::new();
+
+        ticker.wait_for_next_frame(&mut clock, start, true);
+        assert_eq!(clock.now(), start);
+        ticker.wait_for_next_frame(&mut clock, start, false);
+        assert_eq!(clock.now(), start + FRAME_INTERVAL);
+    }
+}
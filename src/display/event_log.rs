@@ -0,0 +1,12 @@
+use crate::game::event::{EventListener, GameEvent};
+
+/// Prints every game event, one per line.
+pub struct EventLog;
+
+impl EventListener for EventLog
+{
+    fn on_event(&mut self, event: &GameEvent)
+    {
+        println!("{event:?}");
+    }
+}
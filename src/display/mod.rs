@@ -1,51 +1,509 @@
-use crate::display::screen::Screen;
-use crate::display::screen::DisplayState::{GameOver, Stop};
-use crate::display::sound::Sound;
-use crate::game::Game;
+use std::time::Duration;
+use crate::campaign::Campaign;
+use crate::config::Config;
+use crate::display::clock::{Clock, Ticker};
+use crate::display::screen::{MusicControl, Screen, TextInput};
+use crate::display::screen::DisplayState::{Cleared, GameOver, GoalReached, Paused, Playing, Stop, TimeUp, Won};
+use crate::display::sound::{Sound, SoundEffect};
+use crate::game::{Game, Mode};
+use crate::game::command::Command;
+use crate::game::event::{EventListener, GameEvent};
+use crate::highscores::{HighScore, HighScores};
+use crate::replay::Replay;
 
+pub mod clock;
+pub mod event_log;
+mod font;
 pub mod screen;
 pub mod sound;
 
+/// Title of the game window, showing the seed so a run can be played again.
+pub fn window_title(seed: u64) -> String
+{
+    format!("Super snake (seed {seed})")
+}
+
+/// Longest name that can be put on the high score table.
+const MAX_NAME_LENGTH: usize = 10;
+/// How long the music volume and track title stay on screen after they change.
+const MUSIC_NOTICE_DURATION: Duration = Duration::from_secs(2);
+/// Change of the music volume per key press.
+const VOLUME_STEP: f32 = 0.1;
+
 pub struct Display<'a>
 {
     game: &'a mut Game,
+    /// Config the game was created from, to create it again on restart.
+    config: Config,
     screen: Screen,
     sound: Option<Sound>,
+    clock: Box<dyn Clock>,
+    ticker: Ticker,
+    recording: Option<(Replay, String)>,
+    playback: Option<Replay>,
+    tick: usize,
+    high_scores: Option<HighScores>,
+    /// Time the current game has been played for, not counting pauses.
+    play_time: Duration,
+    /// Whether the current game, once over, has been checked against the high scores.
+    is_score_checked: bool,
+    /// Name typed so far while a run that made the high score table is waiting for one.
+    name_entry: Option<String>,
+    campaign: Option<Campaign>,
+    /// Whether the game waits behind a banner, such as a level intro, for a key press.
+    is_banner_shown: bool,
+    listeners: Vec<Box<dyn EventListener>>,
+    /// Title of the track last shown on screen.
+    track_title: Option<String>,
+    /// When the music notice comes off the screen, on the clock.
+    music_notice_end: Duration,
 }
 
 impl <'a> Display<'a>
 {
-    pub fn new(game: &'a mut Game, screen: Screen, sound: Option<Sound>) -> Display
+    pub fn new(game: &'a mut Game, config: &Config, screen: Screen, sound: Option<Sound>,
+               clock: Box<dyn Clock>) -> Display<'a>
     {
         Display
         {
             game,
+            config: config.clone(),
             screen,
             sound,
+            clock,
+            ticker: Ticker::new(),
+            recording: None,
+            playback: None,
+            tick: 0,
+            high_scores: None,
+            play_time: Duration::ZERO,
+            is_score_checked: false,
+            name_entry: None,
+            campaign: None,
+            is_banner_shown: false,
+            listeners: vec![],
+            track_title: None,
+            music_notice_end: Duration::ZERO,
+        }
+    }
+
+    /// Records every tick's input into `replay`, saved to `filename` when the run ends.
+    pub fn record(&mut self, replay: Replay, filename: &str)
+    {
+        self.recording = Some((replay, filename.to_string()));
+    }
+
+    /// Drives the game from `replay` instead of the keyboard.
+    pub fn play_back(&mut self, replay: Replay)
+    {
+        self.playback = Some(replay);
+    }
+
+    /// Puts runs that are good enough on `high_scores` and shows the table when a game is over.
+    pub fn keep_high_scores(&mut self, high_scores: HighScores)
+    {
+        self.high_scores = Some(high_scores);
+    }
+
+    /// Applies the music keys pressed since the last frame.
+    fn control_music(&mut self)
+    {
+        let controls = self.screen.get_music_controls();
+        let sound = match &mut self.sound
+        {
+            Some(s) => s,
+            None => return,
+        };
+
+        for control in &controls
+        {
+            match control
+            {
+                MusicControl::Mute => sound.toggle_mute(),
+                MusicControl::VolumeUp => sound.change_volume(VOLUME_STEP),
+                MusicControl::VolumeDown => sound.change_volume(-VOLUME_STEP),
+                MusicControl::NextTrack => sound.next_track(),
+            }
+        }
+
+        if !controls.is_empty()
+        {
+            self.show_music_notice();
+        }
+    }
+
+    /// Shows the music volume and the track playing for a moment.
+    fn show_music_notice(&mut self)
+    {
+        let sound = match &self.sound
+        {
+            Some(s) => s,
+            None => return,
+        };
+
+        let volume = match sound.is_muted()
+        {
+            true => "MUSIC MUTED".to_string(),
+            false => format!("VOLUME {}", (sound.get_volume() * 100.0).round()),
+        };
+        let mut lines = vec![volume];
+        lines.extend(sound.get_track_title().map(|title| title.to_uppercase()));
+
+        self.screen.set_notice(lines);
+        self.music_notice_end = self.clock.now() + MUSIC_NOTICE_DURATION;
+    }
+
+    /// Shows the music notice when a new track starts, and takes it off once its time is up.
+    fn update_music_notice(&mut self)
+    {
+        let title = self.sound.as_ref().and_then(|s| s.get_track_title().cloned());
+        if title.is_some() && title != self.track_title
+        {
+            self.track_title = title;
+            self.show_music_notice();
+        }
+        else if self.clock.now() >= self.music_notice_end
+        {
+            self.screen.set_notice(vec![]);
+        }
+    }
+
+    /// Tells `listener` about everything that happens in the game from now on.
+    pub(crate) fn subscribe(&mut self, listener: Box<dyn EventListener>)
+    {
+        self.listeners.push(listener);
+    }
+
+    fn notify(&mut self, event: &GameEvent)
+    {
+        for listener in &mut self.listeners
+        {
+            listener.on_event(event);
+        }
+    }
+
+    /// Plays through the levels of `campaign`, starting with its current level, whose config
+    /// the game was created from.
+    pub fn play_campaign(&mut self, campaign: Campaign)
+    {
+        self.campaign = Some(campaign);
+        self.show_level_intro(None);
+    }
+
+    /// Holds the game behind a banner naming the current level and its goal,
+    /// under `headline` if there is one.
+    fn show_level_intro(&mut self, headline: Option<&str>)
+    {
+        let campaign = match &self.campaign
+        {
+            Some(c) => c,
+            None => return,
+        };
+
+        let level = campaign.get_level();
+        let mut lines: Vec<String> = headline.iter().map(|h| h.to_string()).collect();
+        lines.extend([format!("LEVEL {}", campaign.get_level_index() + 1),
+                      level.name.to_uppercase(),
+                      level.goal.describe(),
+                      String::new(),
+                      "PRESS ENTER".to_string()]);
+
+        self.screen.set_banner(lines);
+        self.is_banner_shown = true;
+    }
+
+    fn hide_banner(&mut self)
+    {
+        self.screen.set_banner(vec![]);
+        self.is_banner_shown = false;
+        self.ticker.restart(self.clock.now());
+    }
+
+    /// Saves the progress and starts the next level of the campaign, or the first one
+    /// again once the last is cleared.
+    fn next_level(&mut self) -> Result<(), String>
+    {
+        let campaign = match &mut self.campaign
+        {
+            Some(c) => c,
+            None => return Ok(()),
+        };
+
+        let has_next = campaign.advance()?;
+        let level = campaign.get_level_index();
+        self.config = campaign.make_config();
+        self.restart()?;
+        self.notify(&GameEvent::LevelChanged { level });
+
+        let headline = match has_next
+        {
+            true => "LEVEL CLEARED",
+            false => "CAMPAIGN COMPLETE",
+        };
+        self.show_level_intro(Some(headline));
+
+        Ok(())
+    }
+
+    /// Returns the input for this tick, or `None` once a playback has run out of inputs.
+    fn next_input(&mut self) -> Option<Vec<Command>>
+    {
+        // Read the keyboard even during playback, so presses don't pile up.
+        let keyboard = self.screen.get_input();
+        let input = match &self.playback
+        {
+            Some(replay) => replay.get_input(self.tick),
+            None => Some(keyboard),
+        };
+
+        self.tick += 1;
+        input
+    }
+
+    fn step_game(&mut self)
+    {
+        if !self.game.is_running()
+        {
+            return;
+        }
+
+        let input = match self.next_input()
+        {
+            Some(i) => i,
+            None => return,
+        };
+
+        self.game.go(&input);
+        for event in self.game.get_events().clone()
+        {
+            self.notify(&event);
+        }
+
+        if let Some((replay, _)) = &mut self.recording
+        {
+            replay.record(input);
         }
     }
 
+    fn tick_interval(&self) -> Duration
+    {
+        Duration::from_secs_f64(1.0 / self.game.get_speed())
+    }
+
+    /// Runs the game ticks that are due by now, at the game's speed whatever the frame rate.
+    fn run_due_ticks(&mut self)
+    {
+        let now = self.clock.now();
+        while self.ticker.is_due(now)
+        {
+            self.step_game();
+            self.ticker.tick(self.tick_interval());
+        }
+    }
+
+    fn save_recording(&mut self) -> Result<(), String>
+    {
+        if let Some((replay, filename)) = &mut self.recording
+        {
+            replay.set_points(self.game.get_points());
+            replay.save(filename)?;
+        }
+
+        Ok(())
+    }
+
+    /// Starts a new game from the same config. A recording only keeps the first game,
+    /// while a replay starts over from its beginning.
+    fn restart(&mut self) -> Result<(), String>
+    {
+        self.save_recording()?;
+        self.recording = None;
+
+        *self.game = Game::new(&self.config)?;
+        self.tick = 0;
+        self.ticker.restart(self.clock.now());
+        self.play_time = Duration::ZERO;
+        self.is_score_checked = false;
+        self.screen.set_game_over_text(vec![], vec![]);
+
+        let seed = self.game.get_seed();
+        println!("Seed: {seed}");
+        self.screen.set_title(&window_title(seed));
+
+        Ok(())
+    }
+
+    /// Lines of the high score table of `mode`, best run first.
+    fn high_score_lines(high_scores: &HighScores, mode: Mode) -> Vec<String>
+    {
+        let mode = mode.get_name().replace('_', " ").to_uppercase();
+        let mut lines = vec![format!("{mode} HIGH SCORES")];
+        for (i, entry) in high_scores.get_entries().iter().enumerate()
+        {
+            lines.push(format!("{:>2} {:<width$} {:>5} {:>4} {:>2}:{:02}",
+                               i + 1, entry.name, entry.score, entry.length,
+                               entry.duration / 60, entry.duration % 60,
+                               width = MAX_NAME_LENGTH));
+        }
+
+        lines
+    }
+
+    fn show_high_scores(&mut self)
+    {
+        let high_scores = match &self.high_scores
+        {
+            Some(h) => h,
+            None => return,
+        };
+
+        let prompt = match &self.name_entry
+        {
+            Some(name) => vec!["NEW HIGH SCORE".to_string(), format!("NAME: {name}_")],
+            None => vec![],
+        };
+
+        self.screen.set_game_over_text(Self::high_score_lines(high_scores, self.config.mode), prompt);
+    }
+
+    /// Asks for a name if the game that just ended made the high score table.
+    /// Runs steered by the autopilot don't count.
+    fn check_score(&mut self)
+    {
+        self.is_score_checked = true;
+
+        let qualifies = match &self.high_scores
+        {
+            Some(h) => h.qualifies(self.game.get_points()) && !self.game.was_autopilot_used(),
+            None => false,
+        };
+        if qualifies
+        {
+            self.name_entry = Some(String::new());
+            if let Some(s) = &self.sound
+            {
+                s.play_effect(SoundEffect::HighScore);
+            }
+        }
+
+        self.show_high_scores();
+    }
+
+    /// Takes the typed keys into the name, putting the run on the table once it is confirmed.
+    fn enter_name(&mut self) -> Result<(), String>
+    {
+        let mut name = match self.name_entry.take()
+        {
+            Some(n) => n,
+            None => return Ok(()),
+        };
+
+        let mut is_confirmed = false;
+        for input in self.screen.get_text_input()
+        {
+            match input
+            {
+                TextInput::Char(c) if name.len() < MAX_NAME_LENGTH => name.push(c),
+                TextInput::Char(_) => {},
+                TextInput::Backspace => { name.pop(); },
+                TextInput::Confirm => is_confirmed = !name.is_empty(),
+            }
+        }
+
+        match (is_confirmed, &mut self.high_scores)
+        {
+            (true, Some(high_scores)) =>
+            {
+                let entry = HighScore::new(&name, self.game.get_points(), self.game.get_length(),
+                                           self.play_time, &self.config, self.game.get_seed());
+                high_scores.insert(entry)?;
+            }
+            _ => self.name_entry = Some(name),
+        }
+
+        self.show_high_scores();
+        Ok(())
+    }
+
     pub fn run(&mut self) -> Result<(), String>
     {
         loop
         {
-            match &mut self.sound
+            let frame_start = self.clock.now();
+
+            let is_playing = self.game.is_running() && !self.game.is_paused() && !self.is_banner_shown;
+            if let (Some(s), true) = (&mut self.sound, is_playing)
+            {
+                s.play()?;
+            }
+
+            if !self.is_banner_shown
+            {
+                self.run_due_ticks();
+            }
+
+            let state = self.screen.draw(self.game)?;
+            // Letters typed into a name are not music keys.
+            if self.name_entry.is_none()
+            {
+                self.control_music();
+            }
+            self.update_music_notice();
+
+            if self.is_banner_shown && self.screen.is_restart_requested()
             {
-                Some(s) => s.play()?,
-                None => {},
-            };
+                self.hide_banner();
+            }
 
-            let status = self.screen.draw(&mut self.game)?;
-            if status == GameOver
+            match state
             {
-                self.sound.take();
+                Playing => {},
+                Paused =>
+                {
+                    if let Some(s) = &self.sound
+                    {
+                        s.pause();
+                    }
+                }
+                GoalReached if self.campaign.is_some() =>
+                {
+                    if let Some(s) = &self.sound
+                    {
+                        s.stop();
+                    }
+                    self.next_level()?;
+                }
+                GameOver | Won(_) | Cleared | GoalReached | TimeUp =>
+                {
+                    if let Some(s) = &self.sound
+                    {
+                        s.stop();
+                    }
+                    if !self.is_score_checked
+                    {
+                        self.check_score();
+                    }
+
+                    if self.name_entry.is_some()
+                    {
+                        self.enter_name()?;
+                    }
+                    else if self.screen.is_restart_requested()
+                    {
+                        self.restart()?;
+                    }
+                }
+                Stop => break,
             }
-            if status == Stop
+
+            // Behind a banner no tick is due, so only the frame counts.
+            self.ticker.wait_for_next_frame(self.clock.as_mut(), frame_start, !self.is_banner_shown);
+            if state == Playing && !self.is_banner_shown
             {
-                break;
+                self.play_time += self.clock.now() - frame_start;
             }
         }
 
-        Ok(())
+        self.save_recording()
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,6 @@
-use crate::game::{Color, Game};
-use minifb::{Key, Window, WindowOptions};
+use crate::game::{Cell, Color, EdgeMode, Game};
+use crate::game::command::{Command, Heading};
+use minifb::{Key, KeyRepeat, Window, WindowOptions};
 
 use std::ops::Range;
 use bmp::Image;
@@ -8,29 +9,98 @@ type Area = (Range<usize>, Range<usize>);
 
 use itertools::Itertools;
 use crate::config::Config;
-use crate::display::screen::DisplayState::{GameOver, Playing, Stop};
+use crate::display::font::{self, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::display::screen::DisplayState::{Cleared, GameOver, GoalReached, Paused, Playing, Stop, TimeUp, Won};
 
 #[derive(PartialEq)]
 pub enum DisplayState
 {
     Playing,
+    Paused,
     GameOver,
+    /// A game with several players is over and the player with this index won it.
+    Won(usize),
+    /// The snakes filled the board, leaving no room for more food.
+    Cleared,
+    /// The goal of the level was met.
+    GoalReached,
+    /// The time of a time attack ran out.
+    TimeUp,
     Stop,
 }
 
+/// A key press while a name is being typed.
+#[derive(PartialEq)]
+pub enum TextInput
+{
+    Char(char),
+    Backspace,
+    Confirm,
+}
+
+/// A key press changing the music.
+#[derive(Clone, Copy, PartialEq)]
+pub enum MusicControl
+{
+    Mute,
+    VolumeUp,
+    VolumeDown,
+    NextTrack,
+}
+
+/// Keys of the music controls, on the main keyboard and the number pad.
+const MUSIC_KEYS: [(Key, MusicControl); 6] =
+[
+    (Key::M, MusicControl::Mute),
+    (Key::Equal, MusicControl::VolumeUp),
+    (Key::NumPadPlus, MusicControl::VolumeUp),
+    (Key::Minus, MusicControl::VolumeDown),
+    (Key::NumPadMinus, MusicControl::VolumeDown),
+    (Key::N, MusicControl::NextTrack),
+];
+
+/// Direction keys of each player.
+const PLAYER_KEYS: [[(Key, Heading); 4]; 2] =
+[
+    [(Key::Left, Heading::Left), (Key::Right, Heading::Right),
+     (Key::Up, Heading::Up), (Key::Down, Heading::Down)],
+    [(Key::A, Heading::Left), (Key::D, Heading::Right),
+     (Key::W, Heading::Up), (Key::S, Heading::Down)],
+];
+
 extern crate bmp;
 
 pub struct Screen
 {
     game_area: Area,
+    /// Part of the game area the board is drawn in, centred and scaled to whole pixels per cell.
+    board_area: Area,
+    /// Width and height of a cell in pixels.
+    cell_size: usize,
     down_bar: Area,
     window: Window,
     pixels: Vec<u32>,
-    delay: u64,
     bitmaps: Vec<Image>,
+    /// Keys pressed since the input was last read, in the order they were pressed.
+    pressed_keys: Vec<Key>,
+    /// Lines shown above the game over text, and below it.
+    game_over_text: (Vec<String>, Vec<String>),
+    /// Lines shown over the darkened game, such as a level intro. Empty for none.
+    banner: Vec<String>,
+    /// Lines shown at the top of the game while it is running, such as the music volume.
+    notice: Vec<String>,
 }
 
-const INITIAL_DELAY: u64 = 80000;
+const EDGE_WIDTH: usize = 2;
+const SOLID_EDGE_COLOR: Color = (255, 255, 255);
+const MIRRORED_EDGE_COLOR: Color = (255, 128, 0);
+const PAUSE_SYMBOL_COLOR: Color = (255, 255, 255);
+/// Keys that start a new game once the current one is over.
+const RESTART_KEYS: [Key; 3] = [Key::R, Key::Enter, Key::Space];
+const TEXT_COLOR: Color = (255, 255, 255);
+/// Size of a font pixel in screen pixels.
+const TEXT_SCALE: usize = 2;
+const LINE_HEIGHT: usize = (GLYPH_HEIGHT + 1) * TEXT_SCALE;
 
 impl Screen
 {
@@ -50,24 +120,42 @@ impl Screen
                     config.screen_height as usize / 4),
             );
 
-        let (game_xs, _game_ys) = game_area.clone();
+        let (game_xs, game_ys) = game_area.clone();
         let (_down_bar_xs, down_bar_ys) = down_bar.clone();
+
+        let (board_width, board_height) = config.get_board_size();
+        let (board_width, board_height) = (board_width.max(1) as usize, board_height.max(1) as usize);
+        let cell_size = (game_xs.len() / board_width).min(game_ys.len() / board_height);
+        if cell_size == 0
+        {
+            let err_msg = format!("A {board_width}x{board_height} board does not fit in a {}x{} window.",
+                                  game_xs.len(), game_ys.len());
+            return Err(err_msg);
+        }
+        let board_left = game_xs.start + game_xs.len().saturating_sub(cell_size * board_width) / 2;
+        let board_top = game_ys.start + game_ys.len().saturating_sub(cell_size * board_height) / 2;
+        let board_area =
+            (
+                (board_left..board_left + cell_size * board_width),
+                (board_top..board_top + cell_size * board_height),
+            );
         let window_width = game_xs.end;
         let window_height = down_bar_ys.end;
 
         let window_creation = Window::new(name, window_width, window_height,
                                           WindowOptions::default());
 
-        let window = match window_creation
+        let mut window = match window_creation
         {
             Ok(win) => win,
             Err(err) =>
                 {
-                    let err_msg = format!("Could not create display: {}\n", err.to_string())
-                        .to_string();
+                    let err_msg = format!("Could not create display: {}\n", err);
                     return Err(err_msg);
                 }
         };
+        // The game loop keeps its own time.
+        window.limit_update_rate(None);
 
         let mut bitmaps = vec![];
         for i in 0..=10
@@ -91,27 +179,112 @@ impl Screen
         let display = Screen
         {
             game_area,
+            board_area,
+            cell_size,
             down_bar,
             window,
             pixels: vec![0; window_width * window_height],
-            delay: 80000,
             bitmaps,
+            pressed_keys: vec![],
+            game_over_text: (vec![], vec![]),
+            banner: vec![],
+            notice: vec![],
         };
 
         Ok(display)
     }
 
-    fn get_input(&self) -> Option<Key>
+    /// Turns the keys pressed since the last call into commands, keeping their order.
+    pub(crate) fn get_input(&mut self) -> Vec<Command>
+    {
+        self.pressed_keys.drain(..)
+            .filter_map(|key| match key
+            {
+                Key::P => Some(Command::Pause),
+                Key::Tab => Some(Command::Autopilot),
+                Key::Q => Some(Command::Quit),
+                _ => PLAYER_KEYS.iter().enumerate()
+                    .find_map(|(player, keys)| keys.iter()
+                        .find(|(player_key, _)| *player_key == key)
+                        .map(|(_, heading)| Command::Turn(player, *heading))),
+            })
+            .collect()
+    }
+
+    /// Takes the music keys out of the keys pressed since the input was last read.
+    pub(crate) fn get_music_controls(&mut self) -> Vec<MusicControl>
     {
-        let keys = [Key::Left, Key::Right, Key::Up, Key::Down];
-        for key in keys
+        let mut controls = vec![];
+        self.pressed_keys.retain(|key|
         {
-            if self.window.is_key_down(key)
+            let control = MUSIC_KEYS.iter()
+                .find(|(music_key, _)| music_key == key)
+                .map(|(_, control)| *control);
+            match control
             {
-                return Some(key);
+                Some(control) =>
+                {
+                    controls.push(control);
+                    false
+                }
+                None => true,
             }
-        }
-        None
+        });
+
+        controls
+    }
+
+    /// Whether a restart key was pressed since the input was last read.
+    pub(crate) fn is_restart_requested(&mut self) -> bool
+    {
+        self.pressed_keys.drain(..).any(|key| RESTART_KEYS.contains(&key))
+    }
+
+    /// Turns the keys pressed since the last call into text input: letters, digits,
+    /// Backspace and Enter.
+    pub(crate) fn get_text_input(&mut self) -> Vec<TextInput>
+    {
+        self.pressed_keys.drain(..)
+            .filter_map(|key| match key
+            {
+                Key::Backspace => Some(TextInput::Backspace),
+                Key::Enter => Some(TextInput::Confirm),
+                // Digits and letters come first in the key enum, in order.
+                _ => char::from_digit(key as u32, 36)
+                    .map(|c| TextInput::Char(c.to_ascii_uppercase())),
+            })
+            .collect()
+    }
+
+    /// Sets the lines shown on the game over screen, `top` above the game over text
+    /// and `bottom` below it.
+    pub(crate) fn set_game_over_text(&mut self, top: Vec<String>, bottom: Vec<String>)
+    {
+        self.game_over_text = (top, bottom);
+    }
+
+    /// Sets the lines shown over the game while it is running. Empty lines hide the banner.
+    pub(crate) fn set_banner(&mut self, lines: Vec<String>)
+    {
+        self.banner = lines;
+    }
+
+    /// Sets the lines shown at the top of the running game. Empty lines hide the notice.
+    pub(crate) fn set_notice(&mut self, lines: Vec<String>)
+    {
+        self.notice = lines;
+    }
+
+    pub(crate) fn set_title(&mut self, title: &str)
+    {
+        self.window.set_title(title);
+    }
+
+    /// Keeps the keys pressed since the last frame, in order, until the input is read.
+    fn collect_pressed_keys(&mut self)
+    {
+        let keys = self.window.get_keys_pressed(KeyRepeat::No);
+        self.pressed_keys.extend(keys);
     }
 
     fn color_to_pixel(color: Color) -> u32
@@ -120,43 +293,57 @@ impl Screen
         ((r as u32) << 16) | ((g as u32) << 8) | (b as u32)
     }
 
+    /// Pixels covered by a cell.
+    fn cell_area(&self, cell: &Cell) -> Area
+    {
+        let (board_xs, board_ys) = &self.board_area;
+        let left = board_xs.start + cell.get_x() as usize * self.cell_size;
+        let top = board_ys.start + cell.get_y() as usize * self.cell_size;
+
+        (left..left + self.cell_size, top..top + self.cell_size)
+    }
+
+    /// Fills the board with the background colour and the rest of the game area with black.
     fn draw_game_background(&mut self, game: &Game)
     {
         let background_color = game.get_background_color();
 
         let (game_xs, game_ys) = self.game_area.clone();
+        let (board_xs, board_ys) = self.board_area.clone();
         let game_width = game_xs.end - game_xs.start;
 
         for (x, y) in game_xs.cartesian_product(game_ys)
         {
             let index = y * game_width + x;
-            let pixel_val = Self::color_to_pixel(background_color);
+            let pixel_val = match board_xs.contains(&x) && board_ys.contains(&y)
+            {
+                true => Self::color_to_pixel(background_color),
+                false => 0,
+            };
             self.pixels[index] = pixel_val;
         }
     }
 
     fn draw_cells(&mut self, game: &Game)
     {
-        // Join snake cells and point cell to display all of them.
-        let mut all_cells = game.get_snake_cells().clone();
-        let point_cell = game.get_point_cell().clone();
-        all_cells.push(point_cell);
+        // Join walls, portals, snake cells and food cells to display all of them.
+        let mut all_cells = game.get_wall_cells().clone();
+        all_cells.extend(game.get_portal_cells());
+        all_cells.extend(game.get_snake_cells());
+        all_cells.extend(game.get_food_cells());
 
         let (game_xs, _game_ys) = self.game_area.clone();
         let game_width = game_xs.end - game_xs.start;
         for cell in all_cells
         {
-            let left = cell.get_left() as usize;
-            let down = cell.get_bottom() as usize;
-            let right = cell.get_right() as usize;
-            let up = cell.get_top() as usize;
+            let (xs, ys) = self.cell_area(&cell);
             let color = cell.get_color();
 
-            for x in left..right
+            for x in xs
             {
-                for y in up..down
+                for y in ys.clone()
                 {
-                    let index = y * game_width as usize + x;
+                    let index = y * game_width + x;
                     let (r, g, b) = color;
                     let pixel_val = ((r as u32) << 16) | ((g as u32) << 8) | (b as u32);
                     self.pixels[index] = pixel_val;
@@ -165,10 +352,118 @@ impl Screen
         }
     }
 
+    /// Power-ups look like food with a hole in the middle.
+    fn draw_power_ups(&mut self, game: &Game)
+    {
+        let (game_xs, _game_ys) = self.game_area.clone();
+        let game_width = game_xs.end - game_xs.start;
+        let background = Self::color_to_pixel(game.get_background_color());
+        let inset = self.cell_size / 3;
+
+        for cell in game.get_power_up_cells()
+        {
+            let (xs, ys) = self.cell_area(&cell);
+            let pixel_val = Self::color_to_pixel(cell.get_color());
+            let hole_xs = xs.start + inset..xs.end - inset;
+            let hole_ys = ys.start + inset..ys.end - inset;
+
+            for (x, y) in xs.cartesian_product(ys)
+            {
+                let in_hole = hole_xs.contains(&x) && hole_ys.contains(&y);
+                self.pixels[y * game_width + x] = if in_hole { background } else { pixel_val };
+            }
+        }
+    }
+
+    /// Paints one edge stripe. Mirrored edges fade along the edge, in opposite
+    /// directions on the two sides, so it is visible which end meets which.
+    fn draw_edge(&mut self, xs: Range<usize>, ys: Range<usize>, mode: EdgeMode, reversed: bool)
+    {
+        if mode == EdgeMode::Wrap
+        {
+            return;
+        }
+
+        let (game_xs, _game_ys) = self.game_area.clone();
+        let game_width = game_xs.end - game_xs.start;
+        let length = (xs.end - xs.start).max(ys.end - ys.start);
+        let along_x = xs.end - xs.start > ys.end - ys.start;
+
+        for (x, y) in xs.clone().cartesian_product(ys.clone())
+        {
+            let color = match mode
+            {
+                EdgeMode::Mirrored =>
+                {
+                    let position = if along_x { x - xs.start } else { y - ys.start };
+                    let position = if reversed { length - 1 - position } else { position };
+                    let shade = 64 + 191 * position / length;
+
+                    let (r, g, b) = MIRRORED_EDGE_COLOR;
+                    let fade = |c: u8| (c as usize * shade / 255) as u8;
+                    (fade(r), fade(g), fade(b))
+                }
+                _ => SOLID_EDGE_COLOR,
+            };
+
+            self.pixels[y * game_width + x] = Self::color_to_pixel(color);
+        }
+    }
+
+    fn draw_edges(&mut self, game: &Game)
+    {
+        let topology = game.get_topology();
+        let (xs, ys) = self.board_area.clone();
+
+        let left = xs.start..xs.start + EDGE_WIDTH;
+        let right = xs.end - EDGE_WIDTH..xs.end;
+        let top = ys.start..ys.start + EDGE_WIDTH;
+        let bottom = ys.end - EDGE_WIDTH..ys.end;
+
+        let mode = topology.left_right;
+        self.draw_edge(left, ys.clone(), mode, false);
+        self.draw_edge(right, ys.clone(), mode, mode == EdgeMode::Mirrored);
+
+        let mode = topology.top_bottom;
+        self.draw_edge(xs.clone(), top, mode, false);
+        self.draw_edge(xs.clone(), bottom, mode, mode == EdgeMode::Mirrored);
+    }
+
+    /// Outlines the cells where heads will come out after crossing a seam.
+    fn draw_exit_markers(&mut self, game: &Game)
+    {
+        for exit in game.get_exit_cells()
+        {
+            self.draw_exit_marker(&exit);
+        }
+    }
+
+    fn draw_exit_marker(&mut self, exit: &Cell)
+    {
+        let (game_xs, _game_ys) = self.game_area.clone();
+        let game_width = game_xs.end - game_xs.start;
+
+        let (xs, ys) = self.cell_area(exit);
+        let (left, right, up, down) = (xs.start, xs.end, ys.start, ys.end);
+        let pixel_val = Self::color_to_pixel(exit.get_color());
+
+        for (x, y) in (left..right).cartesian_product(up..down)
+        {
+            let on_border = x == left || x == right - 1 || y == up || y == down - 1;
+            if on_border
+            {
+                self.pixels[y * game_width + x] = pixel_val;
+            }
+        }
+    }
+
     fn draw_game_area(&mut self, game: &Game) -> Result<(), String>
     {
         self.draw_game_background(game);
         self.draw_cells(game);
+        self.draw_power_ups(game);
+        self.draw_edges(game);
+        self.draw_exit_markers(game);
 
         Ok(())
     }
@@ -213,17 +508,120 @@ impl Screen
         Ok(())
     }
 
-    fn draw_points(&mut self, game: &Game) -> Result<(), String>
+    fn draw_number(&mut self, number: u64, position: usize) -> Result<usize, String>
     {
         let digit_width = self.bitmaps[0].get_width() as usize;
-        let points = game.get_points().to_string();
-        let mut digit_position = 0;
-        for digit in points.chars()
+        let mut digit_position = position;
+        for digit in number.to_string().chars()
         {
             self.draw_digit(digit, digit_position)?;
             digit_position += digit_width;
         }
 
+        Ok(digit_position)
+    }
+
+    fn fill_down_bar_rect(&mut self, xs: Range<usize>, ys: Range<usize>, color: Color)
+    {
+        let (bar_xs, _bar_ys) = self.down_bar.clone();
+        let bar_width = bar_xs.end - bar_xs.start;
+        let pixel_val = Self::color_to_pixel(color);
+
+        for (x, y) in xs.cartesian_product(ys)
+        {
+            self.pixels[y * bar_width + x] = pixel_val;
+        }
+    }
+
+    /// Each player's score, followed by a square per life in the colour of their head
+    /// and the effects working on them.
+    fn draw_points(&mut self, game: &Game) -> Result<(), String>
+    {
+        let (xs, ys) = self.down_bar.clone();
+        let bar_width = xs.end - xs.start;
+        let player_count = game.get_player_count();
+        let margin = 10;
+
+        for player in 0..player_count
+        {
+            let position = player * bar_width / player_count;
+            let digits_end = self.draw_number(game.get_player_points(player), position)?;
+
+            let color = game.get_player_color(player);
+            for life in 0..game.get_player_lives(player) as usize
+            {
+                let left = xs.start + digits_end + margin + life * 2 * margin;
+                if left + margin > xs.end
+                {
+                    break;
+                }
+                self.fill_down_bar_rect(left..left + margin, ys.start + margin..ys.start + 2 * margin,
+                                        color);
+            }
+
+            let section_end = xs.start + (player + 1) * bar_width / player_count;
+            self.draw_effects(game, player,
+                              xs.start + digits_end + margin..section_end.saturating_sub(margin));
+        }
+
+        Ok(())
+    }
+
+    /// A shrinking bar in the power-up's colour for every effect working on a player,
+    /// stacked under their lives within `xs`.
+    fn draw_effects(&mut self, game: &Game, player: usize, xs: Range<usize>)
+    {
+        let (_bar_xs, bar_ys) = self.down_bar.clone();
+        let margin = 10;
+        let max_length = xs.end.saturating_sub(xs.start);
+
+        for (i, (color, left, total)) in game.get_player_effects(player).into_iter().enumerate()
+        {
+            let top = bar_ys.start + 3 * margin + i * 2 * margin;
+            if top + margin > bar_ys.end
+            {
+                break;
+            }
+
+            let length = max_length * left as usize / total.max(1) as usize;
+            self.fill_down_bar_rect(xs.start..xs.start + length, top..top + margin, color);
+        }
+    }
+
+    /// Shrinking bar along the bottom of the down bar, showing how long the timed food lasts.
+    fn draw_timed_food_countdown(&mut self, game: &Game)
+    {
+        let (left, lifetime) = match game.get_timed_food_ticks()
+        {
+            Some(ticks) => ticks,
+            None => return,
+        };
+
+        let (xs, ys) = self.down_bar.clone();
+        let bar_width = xs.end - xs.start;
+        let margin = 10;
+        let max_length = bar_width - 2 * margin;
+        let length = max_length * left as usize / lifetime.max(1) as usize;
+
+        let countdown_xs = xs.start + margin..xs.start + margin + length;
+        let countdown_ys = ys.end - 2 * margin..ys.end - margin;
+        self.fill_down_bar_rect(countdown_xs, countdown_ys, game.get_timed_food_color());
+    }
+
+    /// Seconds left in a time attack, in the top right corner of the down bar.
+    fn draw_time_left(&mut self, game: &Game) -> Result<(), String>
+    {
+        let seconds = match game.get_time_left()
+        {
+            Some(s) => s,
+            None => return Ok(()),
+        };
+
+        let (xs, _ys) = self.down_bar.clone();
+        let digit_width = self.bitmaps[0].get_width() as usize;
+        let width = seconds.to_string().len() * digit_width;
+        self.draw_number(seconds, (xs.end - xs.start).saturating_sub(width))?;
+
         Ok(())
     }
 
@@ -232,52 +630,203 @@ impl Screen
         self.draw_game_area(game)?;
         self.draw_down_bar();
         self.draw_points(game)?;
+        self.draw_timed_food_countdown(game);
+        self.draw_time_left(game)?;
 
         Ok(())
     }
 
+    fn darken_game_area(&mut self)
+    {
+        let (xs, ys) = self.game_area.clone();
+        let width = xs.end - xs.start;
+
+        for (x, y) in xs.cartesian_product(ys)
+        {
+            let pixel = &mut self.pixels[y * width + x];
+            *pixel = (*pixel >> 1) & 0x7f7f7f;
+        }
+    }
+
+    /// Darkens the game area and puts a pause symbol in the middle of it.
+    fn draw_pause_overlay(&mut self)
+    {
+        self.darken_game_area();
+
+        let (xs, ys) = self.game_area.clone();
+        let width = xs.end - xs.start;
+
+        // Two upright bars, as on a media player.
+        let (center_x, center_y) = ((xs.start + xs.end) / 2, (ys.start + ys.end) / 2);
+        let bar_height = (ys.end - ys.start) / 5;
+        let bar_width = bar_height / 3;
+        let bar_ys = center_y - bar_height / 2..center_y + bar_height / 2;
+        let bar_xs = [center_x - bar_width * 3 / 2..center_x - bar_width / 2,
+                      center_x + bar_width / 2..center_x + bar_width * 3 / 2];
+
+        for bar in bar_xs
+        {
+            for (x, y) in bar.cartesian_product(bar_ys.clone())
+            {
+                self.pixels[y * width + x] = Self::color_to_pixel(PAUSE_SYMBOL_COLOR);
+            }
+        }
+    }
+
+    /// Stretches the game over picture over the game area.
     fn draw_game_over(&mut self)
     {
         let (game_area_xs, game_area_ys) = self.game_area.clone();
         let game_area_width = game_area_xs.end - game_area_xs.start;
+        let game_area_height = game_area_ys.end - game_area_ys.start;
 
         let game_over_img = &self.bitmaps[10];
+        let (image_width, image_height) = (game_over_img.get_width() as usize,
+                                           game_over_img.get_height() as usize);
 
         for (x, y) in game_area_xs.cartesian_product(game_area_ys)
         {
-            let pixel = game_over_img.get_pixel(x as u32, y as u32);
+            let image_x = (x - self.game_area.0.start) * image_width / game_area_width;
+            let image_y = (y - self.game_area.1.start) * image_height / game_area_height;
+            let pixel = game_over_img.get_pixel(image_x as u32, image_y as u32);
             let pixel_value = Self::color_to_pixel((pixel.r, pixel.g, pixel.b));
             let index = y * game_area_width + x;
             self.pixels[index] = pixel_value;
         }
     }
 
-    fn game_pace_to_delay(pace: u64) -> u64
+    /// Writes a line of text centred across the game area, with its top at `top`.
+    /// Whatever doesn't fit is cut off.
+    fn draw_text(&mut self, text: &str, top: usize)
     {
-        if INITIAL_DELAY >= pace
-        {INITIAL_DELAY - pace}
-        else { 0 }
+        let (xs, ys) = self.game_area.clone();
+        let width = xs.end - xs.start;
+        let advance = (GLYPH_WIDTH + 1) * TEXT_SCALE;
+        let text_width = text.chars().count() * advance;
+        let left = xs.start + width.saturating_sub(text_width) / 2;
+        let pixel_val = Self::color_to_pixel(TEXT_COLOR);
+
+        for (i, character) in text.chars().enumerate()
+        {
+            let rows = font::glyph(character);
+            let glyph_left = left + i * advance;
+
+            for (row, column) in (0..GLYPH_HEIGHT).cartesian_product(0..GLYPH_WIDTH)
+            {
+                if rows[row] & (1 << (GLYPH_WIDTH - 1 - column)) == 0
+                {
+                    continue;
+                }
+
+                let x = glyph_left + column * TEXT_SCALE;
+                let y = top + row * TEXT_SCALE;
+                for (x, y) in (x..x + TEXT_SCALE).cartesian_product(y..y + TEXT_SCALE)
+                {
+                    if xs.contains(&x) && ys.contains(&y)
+                    {
+                        self.pixels[y * width + x] = pixel_val;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Writes the game over lines in the empty space above and below the game over text.
+    fn draw_game_over_text(&mut self)
+    {
+        let (_xs, ys) = self.game_area.clone();
+        let (top, bottom) = self.game_over_text.clone();
+
+        for (i, line) in top.iter().enumerate()
+        {
+            self.draw_text(line, ys.start + TEXT_SCALE + i * LINE_HEIGHT);
+        }
+
+        let bottom_start = ys.start + (ys.end - ys.start) * 2 / 3;
+        for (i, line) in bottom.iter().enumerate()
+        {
+            self.draw_text(line, bottom_start + i * LINE_HEIGHT);
+        }
+    }
+
+    /// The final board, darkened, with `news` written across the middle.
+    fn draw_game_result(&mut self, game: &Game, news: &str) -> Result<(), String>
+    {
+        self.draw_game(game)?;
+        self.darken_game_area();
+
+        let (_xs, ys) = self.game_area.clone();
+        self.draw_text(news, (ys.start + ys.end - LINE_HEIGHT) / 2);
+        self.draw_game_over_text();
+
+        Ok(())
     }
 
-    pub fn draw(&mut self, game: &mut Game) -> Result<DisplayState, String>
+    /// Darkens the game area and writes the banner lines in the middle of it.
+    fn draw_banner(&mut self)
     {
-        let pace = game.get_pace();
-        self.delay = Self::game_pace_to_delay(pace);
+        self.darken_game_area();
 
-        // Limit fps;
-        self.window.limit_update_rate(Some(std::time::Duration::from_micros(self.delay)));
+        let (_xs, ys) = self.game_area.clone();
+        let height = self.banner.len() * LINE_HEIGHT;
+        let top = ys.start + (ys.end - ys.start).saturating_sub(height) / 2;
+        for (i, line) in self.banner.clone().iter().enumerate()
+        {
+            self.draw_text(line, top + i * LINE_HEIGHT);
+        }
+    }
 
+    /// Paints the down bar in the winner's colour with their player number on it.
+    fn draw_winner(&mut self, game: &Game, winner: usize) -> Result<(), String>
+    {
+        let (xs, ys) = self.down_bar.clone();
+        self.fill_down_bar_rect(xs, ys, game.get_player_color(winner));
+        self.draw_number(winner as u64 + 1, 0)?;
 
-        let input = self.get_input();
+        Ok(())
+    }
 
+    /// Shows one frame of the game and takes in the keys pressed since the last one.
+    pub fn draw(&mut self, game: &Game) -> Result<DisplayState, String>
+    {
         if game.is_running()
         {
-            game.go(input);
             self.draw_game(game)?;
+            if !self.banner.is_empty()
+            {
+                self.draw_banner();
+            }
+            else if game.is_paused()
+            {
+                self.draw_pause_overlay();
+            }
+
+            let (_xs, ys) = self.game_area.clone();
+            for (i, line) in self.notice.clone().iter().enumerate()
+            {
+                self.draw_text(line, ys.start + TEXT_SCALE + i * LINE_HEIGHT);
+            }
+        }
+        else if game.is_time_up()
+        {
+            self.draw_game_result(game, "TIME UP")?;
+        }
+        else if game.is_goal_reached()
+        {
+            self.draw_game_result(game, "GOAL REACHED")?;
+        }
+        else if game.is_board_cleared()
+        {
+            self.draw_game_result(game, "BOARD CLEARED")?;
         }
         else
         {
             self.draw_game_over();
+            self.draw_game_over_text();
+            if let Some(winner) = game.get_winner()
+            {
+                self.draw_winner(game, winner)?;
+            }
         }
 
         let (width, height) = self.window.get_size();
@@ -288,22 +837,28 @@ impl Screen
             Ok(_) => {},
             Err(err) =>
                 {
-                    let err_msg = format!("Could not display to the window. {}", err.to_string())
-                        .to_string();
+                    let err_msg = format!("Could not display to the window. {}", err);
                     return Err(err_msg);
                 }
         }
 
+        self.collect_pressed_keys();
+
         let window_open = self.window.is_open() && !self.window.is_key_down(Key::Escape);
         if !window_open
         {
             return Ok(Stop);
         }
 
-        return match game.is_running()
+        match (game.is_running(), game.get_winner())
         {
-            true => Ok(Playing),
-            false => Ok(GameOver),
-        };
+            (true, _) if game.is_paused() => Ok(Paused),
+            (false, _) if game.is_time_up() => Ok(TimeUp),
+            (false, _) if game.is_goal_reached() => Ok(GoalReached),
+            (false, _) if game.is_board_cleared() => Ok(Cleared),
+            (true, _) => Ok(Playing),
+            (false, Some(winner)) => Ok(Won(winner)),
+            (false, None) => Ok(GameOver),
+        }
     }
 }
@@ -2,21 +2,123 @@ use std::fs::File;
 use std::io::BufReader;
 use rand::prelude::SliceRandom;
 use rand::thread_rng;
-use rodio::{Decoder, OutputStream, Sink};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+use rodio::source::Buffered;
+use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
+use crate::game::event::{EventListener, GameEvent};
 
 type MusicSource = Decoder<BufReader<File>>;
 
+/// Loudest the music can be turned up to, twice as loud as recorded.
+const MAX_VOLUME: f32 = 2.0;
+/// A clip decoded once and kept in memory, so it can be played again and again.
+type EffectSource = Buffered<Decoder<BufReader<File>>>;
+
+/// Short clips played over the music.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum SoundEffect
+{
+    Eat,
+    Turn,
+    PowerUp,
+    Death,
+    HighScore,
+}
+
+/// Paths of the sound effect clips. Effects without a clip stay silent.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub(crate) struct SoundEffectsConfig
+{
+    pub(crate) eat: Option<String>,
+    pub(crate) turn: Option<String>,
+    pub(crate) power_up: Option<String>,
+    pub(crate) death: Option<String>,
+    pub(crate) high_score: Option<String>,
+    /// Loudness of the clips, 1 playing them as recorded.
+    pub(crate) volume: f32,
+}
+
+impl Default for SoundEffectsConfig
+{
+    /// No clips at all.
+    fn default() -> SoundEffectsConfig
+    {
+        SoundEffectsConfig
+        {
+            eat: None,
+            turn: None,
+            power_up: None,
+            death: None,
+            high_score: None,
+            volume: 1.0,
+        }
+    }
+}
+
+/// Plays the clips of the effects channel as things happen in the game.
+#[derive(Clone)]
+pub(crate) struct EffectsPlayer
+{
+    /// Mixes the clips straight into the stream, next to the music sink.
+    stream_handle: OutputStreamHandle,
+    effects: Vec<(SoundEffect, EffectSource)>,
+    volume: f32,
+}
+
+impl EffectsPlayer
+{
+    /// Plays the clip of `effect` over the music, if it has one.
+    pub(crate) fn play(&self, effect: SoundEffect)
+    {
+        let source = match self.effects.iter().find(|(e, _)| *e == effect)
+        {
+            Some((_, s)) => s.clone(),
+            None => return,
+        };
+
+        // A clip that can't be played is skipped rather than stopping the game.
+        let _ = self.stream_handle.play_raw(source.amplify(self.volume).convert_samples());
+    }
+}
+
+impl EventListener for EffectsPlayer
+{
+    /// Only the turns of people are heard, as computer players and the autopilot turn
+    /// all the time.
+    fn on_event(&mut self, event: &GameEvent)
+    {
+        let effect = match event
+        {
+            GameEvent::FoodEaten { .. } => SoundEffect::Eat,
+            GameEvent::Turned { by_person: true, .. } => SoundEffect::Turn,
+            GameEvent::PowerUpStarted { .. } => SoundEffect::PowerUp,
+            GameEvent::Died { .. } => SoundEffect::Death,
+            _ => return,
+        };
+        self.play(effect);
+    }
+}
+
 pub struct Sound
 {
-    music_path: String,
+    /// Where the music comes from, once some has been found there.
+    music_path: Option<String>,
     _stream: OutputStream,
     sink: Sink,
+    /// Titles of the tracks in the current shuffle, in the order they play.
+    titles: Vec<String>,
+    /// Music volume, kept while muted.
+    volume: f32,
+    is_muted: bool,
+    effects: EffectsPlayer,
 }
 
 impl Sound
 {
-    pub fn new(music_path: &String) -> Result<Sound, String>
+    /// Opens the output stream with the effects loaded and no music yet.
+    pub fn new(volume: f32, effects_config: &SoundEffectsConfig) -> Result<Sound, String>
     {
         let (stream, stream_handle) = match OutputStream::try_default()
         {
@@ -38,19 +140,31 @@ impl Sound
             }
         };
 
-        Self::attach_sources(&sink, music_path)?;
+        let effects = EffectsPlayer
+        {
+            stream_handle,
+            effects: Self::load_effects(effects_config)?,
+            volume: effects_config.volume,
+        };
+        let volume = volume.clamp(0.0, MAX_VOLUME);
+        sink.set_volume(volume);
 
         let sound = Sound
         {
-            music_path: music_path.clone(),
+            music_path: None,
             _stream: stream,
             sink,
+            titles: vec![],
+            volume,
+            is_muted: false,
+            effects,
         };
 
         Ok(sound)
     }
 
-    fn get_music_sources(path: &String) -> Result<Vec<MusicSource>, String>
+    /// Music files under `path` with their titles, in random order.
+    fn get_music_sources(path: &String) -> Result<Vec<(String, MusicSource)>, String>
     {
         let mut sources = vec![];
 
@@ -64,6 +178,11 @@ impl Sound
             };
 
             let name = entry.path().display().to_string();
+            let title = match entry.path().file_stem()
+            {
+                Some(stem) => stem.to_string_lossy().to_string(),
+                None => name.clone(),
+            };
 
             let file = match File::open(name)
             {
@@ -73,10 +192,9 @@ impl Sound
 
             let source = Decoder::new(BufReader::new(file));
 
-            match source
+            if let Ok(s) = source
             {
-                Ok(s) => sources.push(s),
-                Err(_) => {},
+                sources.push((title, s));
             }
         }
 
@@ -91,24 +209,130 @@ impl Sound
         Ok(sources)
     }
 
-    fn attach_sources(sink: &Sink, path: &String) -> Result<(), String>
+    fn load_effects(config: &SoundEffectsConfig) -> Result<Vec<(SoundEffect, EffectSource)>, String>
+    {
+        let clips = [(SoundEffect::Eat, &config.eat), (SoundEffect::Turn, &config.turn),
+                     (SoundEffect::PowerUp, &config.power_up), (SoundEffect::Death, &config.death),
+                     (SoundEffect::HighScore, &config.high_score)];
+
+        let mut effects = vec![];
+        for (effect, path) in clips
+        {
+            let path = match path
+            {
+                Some(p) => p,
+                None => continue,
+            };
+
+            let file = match File::open(path)
+            {
+                Ok(f) => f,
+                Err(e) => return Err(format!("Could not open sound effect {path}: {e}")),
+            };
+
+            match Decoder::new(BufReader::new(file))
+            {
+                Ok(source) => effects.push((effect, source.buffered())),
+                Err(e) => return Err(format!("Could not decode sound effect {path}: {e}")),
+            }
+        }
+
+        Ok(effects)
+    }
+
+    /// Queues a fresh shuffle of the music, returning the titles in the order they play.
+    fn attach_sources(sink: &Sink, path: &String) -> Result<Vec<String>, String>
     {
         let sources = Self::get_music_sources(path)?;
-        for source in sources
+        let mut titles = vec![];
+        for (title, source) in sources
         {
             sink.append(source);
+            titles.push(title);
         }
 
+        Ok(titles)
+    }
+
+    /// Queues the music under `path`. Without it the effects still play, over silence.
+    pub fn load_music(&mut self, path: &String) -> Result<(), String>
+    {
+        self.titles = Self::attach_sources(&self.sink, path)?;
+        self.music_path = Some(path.clone());
         Ok(())
     }
 
+    /// Keeps the music going, starting a new shuffle once the last one has ended or been stopped.
     pub fn play(&mut self) -> Result<(), String>
     {
-        if self.sink.empty()
+        self.sink.play();
+        if let (true, Some(path)) = (self.sink.empty(), &self.music_path)
         {
-            Self::attach_sources(&self.sink, &self.music_path)?;
+            self.titles = Self::attach_sources(&self.sink, path)?;
         }
 
         Ok(())
     }
+
+    pub fn pause(&self)
+    {
+        self.sink.pause();
+    }
+
+    /// Ends the current shuffle; the next `play` starts a fresh one.
+    pub fn stop(&self)
+    {
+        self.sink.stop();
+    }
+
+    pub(crate) fn is_muted(&self) -> bool
+    {
+        self.is_muted
+    }
+
+    /// Silences the music, or brings it back at the volume it had.
+    pub(crate) fn toggle_mute(&mut self)
+    {
+        self.is_muted = !self.is_muted;
+        self.sink.set_volume(if self.is_muted { 0.0 } else { self.volume });
+    }
+
+    pub(crate) fn get_volume(&self) -> f32
+    {
+        self.volume
+    }
+
+    /// Turns the music up or down by `change`, unmuting it.
+    pub(crate) fn change_volume(&mut self, change: f32)
+    {
+        self.volume = (self.volume + change).clamp(0.0, MAX_VOLUME);
+        self.is_muted = false;
+        self.sink.set_volume(self.volume);
+    }
+
+    /// Skips the rest of the track playing now.
+    pub(crate) fn next_track(&self)
+    {
+        self.sink.skip_one();
+    }
+
+    /// Title of the track playing now, taken from its file name.
+    pub(crate) fn get_track_title(&self) -> Option<&String>
+    {
+        let played = self.titles.len().checked_sub(self.sink.len())?;
+        self.titles.get(played)
+    }
+
+    /// A player of the sound effects, to be told about the game's events. It only sounds
+    /// as long as this sound is around.
+    pub(crate) fn make_effects_player(&self) -> EffectsPlayer
+    {
+        self.effects.clone()
+    }
+
+    /// Plays the clip of `effect` over the music, if it has one.
+    pub(crate) fn play_effect(&self, effect: SoundEffect)
+    {
+        self.effects.play(effect);
+    }
 }
@@ -0,0 +1,256 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use crate::game::cell::{Direction, Grid};
+use crate::game::command::Heading;
+
+type Position = (i64, i64);
+
+pub(crate) const HEADINGS: [Heading; 4] = [Heading::Up, Heading::Down, Heading::Left, Heading::Right];
+
+/// How close, in steps, an opponent has to be before an aggressive snake goes after it.
+const AMBUSH_RANGE: i64 = 10;
+
+/// How a computer-controlled snake picks its moves.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Difficulty
+{
+    /// Heads straight for the closest food, only avoiding the very next cell if it is taken.
+    Greedy,
+    /// Follows the shortest path to food and avoids moves that lead into dead ends.
+    Pathfinding,
+    /// Tries to cut off the closest opponent, otherwise behaves like `Pathfinding`.
+    Aggressive,
+}
+
+/// Snapshot of the board as one snake sees it, in grid coordinates.
+pub(crate) struct Board
+{
+    pub(crate) grid: Grid,
+    /// Cells a snake cannot enter: walls, snake bodies and poison.
+    pub(crate) blocked: Vec<bool>,
+    pub(crate) portals: Vec<(Position, Position)>,
+    pub(crate) foods: Vec<Position>,
+    pub(crate) poison: Vec<Position>,
+    /// Heads of the other snakes together with the direction they move in.
+    pub(crate) opponents: Vec<(Position, Direction)>,
+    pub(crate) head: Position,
+    /// Cells of the snake itself, from the head to the tail.
+    pub(crate) body: Vec<Position>,
+    pub(crate) direction: Direction,
+    pub(crate) length: usize,
+    /// Every how many ticks the snake grows without eating, as in survival mode.
+    pub(crate) growth_interval: Option<u64>,
+    /// Ticks played so far, counting the one the snake is moving in.
+    pub(crate) tick: u64,
+}
+
+impl Board
+{
+    fn index(&self, (x, y): Position) -> usize
+    {
+        (y * self.grid.get_width() + x) as usize
+    }
+
+    pub(crate) fn is_free(&self, position: Position) -> bool
+    {
+        !self.blocked[self.index(position)]
+    }
+
+    /// Lets the snake move over poison, for when eating it only costs points.
+    pub(crate) fn unblock_poison(&mut self)
+    {
+        for position in self.poison.clone()
+        {
+            let index = self.index(position);
+            self.blocked[index] = false;
+        }
+    }
+
+    /// Where a step from `position` in `direction` lands, following seams and portals,
+    /// or `None` if it runs into a solid edge.
+    pub(crate) fn next_position(&self, position: Position, direction: Direction) -> Option<Position>
+    {
+        let position = self.grid.step_from(position, direction)?;
+
+        let exit = self.portals.iter().find_map(|(a, b)|
+        {
+            if *a == position { Some(*b) }
+            else if *b == position { Some(*a) }
+            else { None }
+        });
+
+        Some(exit.unwrap_or(position))
+    }
+
+    /// Free neighbours of a position, with the direction leading to each.
+    fn free_neighbours(&self, position: Position) -> Vec<(Direction, Position)>
+    {
+        HEADINGS.iter()
+            .map(|heading| Direction::from_heading(*heading))
+            .filter_map(|direction| self.next_position(position, direction)
+                .map(|next| (direction, next)))
+            .filter(|(_, next)| self.is_free(*next))
+            .collect()
+    }
+
+    /// Number of steps from `start` to the closest of `targets` over free cells.
+    pub(crate) fn distance(&self, start: Position, targets: &[Position]) -> Option<usize>
+    {
+        if targets.contains(&start)
+        {
+            return Some(0);
+        }
+
+        let mut visited = vec![false; self.blocked.len()];
+        let mut queue = VecDeque::from([(start, 0)]);
+        visited[self.index(start)] = true;
+
+        while let Some((position, distance)) = queue.pop_front()
+        {
+            for (_, next) in self.free_neighbours(position)
+            {
+                if targets.contains(&next)
+                {
+                    return Some(distance + 1);
+                }
+
+                let index = self.index(next);
+                if !visited[index]
+                {
+                    visited[index] = true;
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Number of free cells reachable from `start`.
+    pub(crate) fn reachable_area(&self, start: Position) -> usize
+    {
+        let mut visited = vec![false; self.blocked.len()];
+        let mut stack = vec![start];
+        visited[self.index(start)] = true;
+        let mut area = 0;
+
+        while let Some(position) = stack.pop()
+        {
+            area += 1;
+            for (_, next) in self.free_neighbours(position)
+            {
+                let index = self.index(next);
+                if !visited[index]
+                {
+                    visited[index] = true;
+                    stack.push(next);
+                }
+            }
+        }
+
+        area
+    }
+
+    /// Moves the snake can make without hitting anything, reversing excluded.
+    /// A snake steps before it turns, so a new heading only counts from the cell
+    /// it is already on its way to.
+    pub(crate) fn get_moves(&self) -> Vec<(Heading, Position)>
+    {
+        let from = match self.next_position(self.head, self.direction)
+        {
+            Some(position) => position,
+            None => return vec![],
+        };
+        let reverse = self.direction.opposite().to_heading();
+
+        HEADINGS.iter()
+            .filter(|heading| Some(**heading) != reverse)
+            .filter_map(|heading|
+            {
+                self.next_position(from, Direction::from_heading(*heading))
+                    .map(|next| (*heading, next))
+            })
+            .filter(|(_, next)| self.is_free(*next) && *next != self.head)
+            .collect()
+    }
+
+    /// Whether the snake grows `ticks` ticks from now whatever it eats.
+    pub(crate) fn grows_in(&self, ticks: u64) -> bool
+    {
+        self.growth_interval.is_some_and(|interval| (self.tick + ticks).is_multiple_of(interval))
+    }
+}
+
+fn manhattan((ax, ay): Position, (bx, by): Position) -> i64
+{
+    (ax - bx).abs() + (ay - by).abs()
+}
+
+/// The move that leaves the snake the most room.
+fn roomiest(board: &Board, moves: &[(Heading, Position)]) -> Option<Heading>
+{
+    moves.iter()
+        .max_by_key(|(_, next)| board.reachable_area(*next))
+        .map(|(heading, _)| *heading)
+}
+
+/// Picks the move with the shortest path to one of `targets`, skipping moves that
+/// would leave the snake less room than its own length. `None` if no such path exists.
+fn towards(board: &Board, moves: &[(Heading, Position)], targets: &[Position]) -> Option<Heading>
+{
+    moves.iter()
+        .filter(|(_, next)| board.reachable_area(*next) >= board.length)
+        .filter_map(|(heading, next)| board.distance(*next, targets)
+            .map(|distance| (distance, *heading)))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, heading)| heading)
+}
+
+/// Chooses the heading for a computer-controlled snake, or `None` when every move is fatal.
+pub(crate) fn choose_heading(board: &Board, difficulty: Difficulty) -> Option<Heading>
+{
+    let moves = board.get_moves();
+    if moves.is_empty()
+    {
+        return None;
+    }
+
+    match difficulty
+    {
+        Difficulty::Greedy =>
+        {
+            moves.iter()
+                .min_by_key(|(_, next)|
+                {
+                    board.foods.iter()
+                        .map(|food| manhattan(*next, *food))
+                        .min()
+                        .unwrap_or(0)
+                })
+                .map(|(heading, _)| *heading)
+        }
+        Difficulty::Pathfinding =>
+        {
+            towards(board, &moves, &board.foods)
+                .or_else(|| roomiest(board, &moves))
+        }
+        Difficulty::Aggressive =>
+        {
+            // Aim two cells in front of the closest nearby opponent's head to cut it off.
+            let ambush = board.opponents.iter()
+                .filter_map(|(head, direction)|
+                {
+                    let first = board.next_position(*head, *direction)?;
+                    board.next_position(first, *direction)
+                })
+                .filter(|position| board.is_free(*position)
+                    && manhattan(board.head, *position) <= AMBUSH_RANGE)
+                .min_by_key(|position| manhattan(board.head, *position));
+
+            ambush.and_then(|target| towards(board, &moves, &[target]))
+                .or_else(|| towards(board, &moves, &board.foods))
+                .or_else(|| roomiest(board, &moves))
+        }
+    }
+}
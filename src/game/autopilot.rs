@@ -0,0 +1,293 @@
+use crate::game::ai::{self, Board, Difficulty, HEADINGS};
+use crate::game::cell::{Direction, Grid};
+use crate::game::command::Heading;
+
+type Position = (i64, i64);
+
+/// A closed path through every cell of an open board, in grid coordinates.
+///
+/// The snake can never run into itself while its head only moves further along the cycle
+/// than where its body still is, however long the snake grows.
+pub(crate) struct Cycle
+{
+    width: i64,
+    /// Place of every cell along the cycle, indexed like the board.
+    order: Vec<usize>,
+}
+
+impl Cycle
+{
+    /// Builds the cycles that sweep the rows or columns back and forth and return along the
+    /// first or last of them, in every orientation and, across seams, shifted half the board.
+    /// Food where one cycle turns lies on a straight stretch of another, where it can be
+    /// eaten on the way through. With an odd number of rows the sweep only closes across a
+    /// seam, so the board may have no cycle at all.
+    pub(crate) fn all(grid: &Grid) -> Vec<Cycle>
+    {
+        let (width, height) = (grid.get_width(), grid.get_height());
+        if width < 2 || height < 2
+        {
+            return vec![];
+        }
+
+        let mut cycles = vec![];
+        for transposed in [false, true]
+        {
+            let (columns, rows) = if transposed { (height, width) } else { (width, height) };
+
+            // Sweeping whole rows closes the cycle over the seam, if the board has one there,
+            // and only ever turns in hairpins. Otherwise the first column is left free to return.
+            let mut sweeps = vec![];
+            for first in [0, 1]
+            {
+                let mut sweep: Vec<Position> = (0..columns).map(|x| (x, 0)).collect();
+                for y in 1..rows
+                {
+                    match y % 2
+                    {
+                        1 => sweep.extend((first..columns).rev().map(|x| (x, y))),
+                        _ => sweep.extend((first..columns).map(|x| (x, y))),
+                    }
+                }
+                sweep.extend((1..rows).rev().filter(|_| first == 1).map(|y| (0, y)));
+                sweeps.push(sweep);
+            }
+
+            for (sweep, (flip_x, flip_y)) in sweeps.iter()
+                .flat_map(|sweep| [(false, false), (true, false), (false, true), (true, true)]
+                    .map(|flips| (sweep, flips)))
+            {
+                for (shift_x, shift_y) in [(0, 0), (width / 2, 0), (0, height / 2), (width / 2, height / 2)]
+                {
+                    let path: Vec<Position> = sweep.iter()
+                        .map(|(x, y)|
+                        {
+                            let (x, y) = if transposed { (*y, *x) } else { (*x, *y) };
+                            let x = if flip_x { width - 1 - x } else { x };
+                            let y = if flip_y { height - 1 - y } else { y };
+                            ((x + shift_x) % width, (y + shift_y) % height)
+                        })
+                        .collect();
+
+                    // Shifted cycles cross the edges of the board, which only some seams allow.
+                    let is_closed = path.iter().zip(path.iter().cycle().skip(1))
+                        .all(|(from, to)| HEADINGS.iter()
+                            .any(|heading| grid.step_from(*from, Direction::from_heading(*heading)) == Some(*to)));
+                    if is_closed
+                    {
+                        let mut order = vec![0; (width * height) as usize];
+                        for (i, (x, y)) in path.into_iter().enumerate()
+                        {
+                            order[(y * width + x) as usize] = i;
+                        }
+                        cycles.push(Cycle { width, order });
+                    }
+                }
+            }
+        }
+
+        cycles
+    }
+
+    fn index_of(&self, (x, y): Position) -> usize
+    {
+        (y * self.width + x) as usize
+    }
+
+    fn place_of(&self, position: Position) -> usize
+    {
+        self.order[self.index_of(position)]
+    }
+
+    /// Steps along the cycle from `from` to `to`.
+    fn distance(&self, from: Position, to: Position) -> usize
+    {
+        let length = self.order.len();
+        (self.place_of(to) + length - self.place_of(from)) % length
+    }
+
+    /// Whether `body`, head first, lies in order along the cycle: each cell further back
+    /// in the body is further back along the cycle from the head, short of the head itself.
+    fn holds(&self, body: &[Position]) -> bool
+    {
+        let places: Vec<usize> = body.iter().skip(1)
+            .map(|cell| self.distance(body[0], *cell))
+            .collect();
+        places.windows(2).all(|pair| pair[0] > pair[1]) && places.iter().all(|place| *place > 0)
+    }
+}
+
+/// The snake's cells, head first, once it has made the move it is already on its way to.
+fn get_body_after_step(board: &Board) -> Option<Vec<Position>>
+{
+    let head = board.next_position(board.head, board.direction)?;
+    let grows = board.foods.contains(&head) || board.grows_in(0);
+
+    let mut body = vec![head];
+    body.extend(&board.body[..board.body.len() - usize::from(!grows)]);
+    Some(body)
+}
+
+fn turns(heading: Heading) -> impl Iterator<Item = Heading>
+{
+    let reverse = Direction::from_heading(heading).opposite().to_heading();
+    HEADINGS.into_iter().filter(move |h| Some(*h) != reverse)
+}
+
+/// The turn that gets the snake soonest to food along any cycle its body lies in order along,
+/// moving the head on along that cycle without passing the tail. From there the snake can
+/// always keep following the cycle, so it never runs into itself.
+fn follow_cycles(board: &Board, cycles: &[Cycle]) -> Option<Heading>
+{
+    let body = get_body_after_step(board)?;
+    let heading = board.direction.to_heading()?;
+    let (head, tail) = (body[0], body[body.len() - 1]);
+    // The tail cannot hold food, so only growing over time keeps it where it is.
+    let tail_leaves = !board.grows_in(1);
+    // Shortcuts leave gaps behind the head, and a tail held up by growth in front of one
+    // would block the way. Snakes that grow over time keep to the cycle with the fewest gaps
+    // in their body instead.
+    let takes_shortcuts = board.growth_interval.is_none();
+    let foods: Vec<Position> = board.foods.iter()
+        .filter(|food| **food != head)
+        .copied()
+        .collect();
+
+    let moves: Vec<(Heading, Position)> = turns(heading)
+        .filter_map(|turn| board.next_position(head, Direction::from_heading(turn))
+            .map(|next| (turn, next)))
+        .filter(|(_, next)| board.is_free(*next) || board.body.contains(next))
+        .collect();
+
+    let mut best = None;
+    for cycle in cycles.iter().filter(|cycle| cycle.holds(&body))
+    {
+        let room = cycle.distance(head, tail);
+        for (turn, next) in &moves
+        {
+            let place = cycle.distance(head, *next);
+            let fits = place < room || (*next == tail && tail_leaves);
+            if !fits || (place > 1 && !takes_shortcuts)
+            {
+                continue;
+            }
+
+            let food_distance = foods.iter().map(|food| cycle.distance(*next, *food)).min();
+            let gaps = match takes_shortcuts
+            {
+                true => 0,
+                false => cycle.order.len() - room,
+            };
+            let key = (gaps, food_distance.unwrap_or(0), place);
+            if best.is_none_or(|(best_key, _)| key < best_key)
+            {
+                best = Some((key, *turn));
+            }
+        }
+    }
+
+    best.map(|(_, turn)| turn)
+}
+
+/// Chooses the autopilot's heading. With cycles the snake follows them, cutting across to
+/// food where it safely can; otherwise it falls back to plain pathfinding.
+pub(crate) fn choose_heading(board: &Board, cycles: &[Cycle]) -> Option<Heading>
+{
+    follow_cycles(board, cycles).or_else(|| ai::choose_heading(board, Difficulty::Pathfinding))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::Cycle;
+    use crate::config::Config;
+    use crate::game::{EdgeMode, Game, Mode, Topology};
+    use crate::game::cell::Grid;
+    use std::ops::Range;
+
+    /// Most ticks a game may take to clear the board.
+    const TICK_LIMIT: usize = 20_000;
+
+    fn config(width: i64, height: i64, edges: EdgeMode, seed: u64) -> Config
+    {
+        let mut config = Config::for_board(width, height);
+        config.autopilot = true;
+        config.seed = Some(seed);
+        config.topology = Topology { left_right: edges, top_bottom: edges };
+        config
+    }
+
+    /// Plays a game on the autopilot until it ends, returning whether the board got cleared.
+    fn clears(config: &Config) -> bool
+    {
+        let mut game = Game::new(config).unwrap();
+        for _ in 0..TICK_LIMIT
+        {
+            if !game.is_running()
+            {
+                break;
+            }
+            game.go(&[]);
+        }
+        game.is_board_cleared()
+    }
+
+    /// Boards of every edge mode and a few shapes, including odd wrapped ones.
+    const BOARDS: [(i64, i64, EdgeMode, &str); 9] =
+        [(4, 4, EdgeMode::Solid, "solid"), (6, 6, EdgeMode::Solid, "solid"), (8, 8, EdgeMode::Solid, "solid"),
+         (4, 4, EdgeMode::Mirrored, "mirrored"), (6, 4, EdgeMode::Mirrored, "mirrored"),
+         (6, 6, EdgeMode::Mirrored, "mirrored"),
+         (5, 5, EdgeMode::Wrap, "wrapped"), (6, 6, EdgeMode::Wrap, "wrapped"), (10, 10, EdgeMode::Wrap, "wrapped")];
+
+    /// Seeds every board is played with.
+    const SEEDS: Range<u64> = 0..40;
+
+    /// Asserts that every seed on every board gets cleared once `tweak` is applied.
+    fn assert_clears_every_board(tweak: impl Fn(&mut Config))
+    {
+        for (width, height, edges, name) in BOARDS
+        {
+            for seed in SEEDS
+            {
+                let mut config = config(width, height, edges, seed);
+                tweak(&mut config);
+                assert!(clears(&config), "{width}x{height} {name} board with seed {seed} was not cleared");
+            }
+        }
+    }
+
+    #[test]
+    fn clears_every_board()
+    {
+        assert_clears_every_board(|_| {});
+    }
+
+    #[test]
+    fn clears_every_board_with_mixed_food()
+    {
+        assert_clears_every_board(|config|
+        {
+            config.food.count = 3;
+            config.food.bonus.weight = 1;
+            config.food.shrinking.weight = 1;
+            config.food.poison.weight = 1;
+            config.food.poison_is_lethal = false;
+            config.food.timed.chance = 0.05;
+            config.food.timed.lifetime = 20;
+        });
+    }
+
+    #[test]
+    fn fills_every_board_in_survival()
+    {
+        assert_clears_every_board(|config| config.mode = Mode::Survival(5));
+    }
+
+    #[test]
+    fn finds_cycles_across_seams_on_odd_boards()
+    {
+        let grid = |edges| Grid { width: 5, height: 5, topology: Topology { left_right: edges, top_bottom: edges } };
+        assert!(!Cycle::all(&grid(EdgeMode::Wrap)).is_empty());
+        assert!(Cycle::all(&grid(EdgeMode::Solid)).is_empty());
+    }
+}
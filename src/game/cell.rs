@@ -1,21 +1,109 @@
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Copy)]
 pub(crate) enum Direction
 {
     UP, DOWN, LEFT, RIGHT, STOP,
 }
-use Direction::{UP, DOWN, LEFT, RIGHT};
+use Direction::{UP, DOWN, LEFT, RIGHT, STOP};
+use crate::game::command::Heading;
+
+impl Direction
+{
+    pub(crate) fn from_heading(heading: Heading) -> Direction
+    {
+        match heading
+        {
+            Heading::Up => UP,
+            Heading::Down => DOWN,
+            Heading::Left => LEFT,
+            Heading::Right => RIGHT,
+        }
+    }
+
+    pub(crate) fn to_heading(self) -> Option<Heading>
+    {
+        match self
+        {
+            UP => Some(Heading::Up),
+            DOWN => Some(Heading::Down),
+            LEFT => Some(Heading::Left),
+            RIGHT => Some(Heading::Right),
+            STOP => None,
+        }
+    }
+
+    pub(crate) fn opposite(&self) -> Direction
+    {
+        match self
+        {
+            UP => DOWN,
+            DOWN => UP,
+            LEFT => RIGHT,
+            RIGHT => LEFT,
+            STOP => STOP,
+        }
+    }
+}
+use crate::game::topology::{EdgeMode, Topology};
 
 type Color = (u8, u8, u8);
 
+/// Board geometry shared by all cells, in cells.
+#[derive(Clone, Copy)]
+pub(crate) struct Grid
+{
+    pub(crate) width: i64,
+    pub(crate) height: i64,
+    pub(crate) topology: Topology,
+}
+
+impl Grid
+{
+    pub(crate) fn get_width(&self) -> i64
+    {
+        self.width
+    }
+
+    pub(crate) fn get_height(&self) -> i64
+    {
+        self.height
+    }
+
+    pub(crate) fn make_cell(&self, x: i64, y: i64, direction: Direction, color: Color) -> Cell
+    {
+        Cell::new(x, y, direction, self.width, self.height, self.topology, color)
+    }
+
+    /// Column and row of a cell.
+    pub(crate) fn position_of(&self, cell: &Cell) -> (i64, i64)
+    {
+        (cell.get_x(), cell.get_y())
+    }
+
+    /// Where a step from `(x, y)` in `direction` lands, or `None` if it runs into a solid edge.
+    pub(crate) fn step_from(&self, (x, y): (i64, i64), direction: Direction) -> Option<(i64, i64)>
+    {
+        let mut cell = self.make_cell(x, y, direction, (0, 0, 0));
+        if cell.get_crossed_edge() == Some(EdgeMode::Solid)
+        {
+            return None;
+        }
+
+        cell.step();
+        Some(self.position_of(&cell))
+    }
+}
+
+/// One square of the board, placed by column and row.
 #[derive(Clone)]
 pub(crate) struct Cell
 {
-    left: i64,
-    top: i64,
-    size: i64,
+    x: i64,
+    y: i64,
     direction: Direction,
-    screen_width: i64,
-    screen_height: i64,
+    board_width: i64,
+    board_height: i64,
+    topology: Topology,
     color: Color,
 }
 
@@ -23,102 +111,125 @@ impl Cell
 {
     pub(crate) fn new
     (
-        left: i64,
-        top: i64,
-        size: i64,
+        x: i64,
+        y: i64,
         direction: Direction,
-        screen_width: i64,
-        screen_height: i64,
+        board_width: i64,
+        board_height: i64,
+        topology: Topology,
         color: Color,
     )
         -> Cell
     {
         Cell
         {
-            left,
-            top,
-            size,
+            x,
+            y,
             direction,
-            screen_width,
-            screen_height,
+            board_width,
+            board_height,
+            topology,
             color,
         }
     }
 
     pub(crate) fn step(&mut self)
     {
-        match self.direction
+        let edge_mode = match self.get_crossed_edge()
         {
-            UP =>
-            {
-                let mut new_top = self.top - self.size;
-                if new_top < 0
-                {
-                    new_top = self.screen_height - self.size;
-                }
-                self.top = new_top;
-            }
-            DOWN =>
+            Some(mode) => mode,
+            None =>
             {
-                let mut new_top = self.top + self.size;
-                let new_bottom = new_top + self.size;
-                if new_bottom > self.screen_height
+                match self.direction
                 {
-                    new_top = 0;
+                    UP => self.y -= 1,
+                    DOWN => self.y += 1,
+                    LEFT => self.x -= 1,
+                    RIGHT => self.x += 1,
+                    _ => {}
                 }
-                self.top = new_top;
+                return;
             }
-            LEFT =>
+        };
+
+        let max_x = self.board_width - 1;
+        let max_y = self.board_height - 1;
+
+        match (self.direction, edge_mode)
+        {
+            // Solid edges are never crossed, the game ends before that.
+            (_, EdgeMode::Solid) => {}
+
+            (UP, _) => self.y = max_y,
+            (DOWN, _) => self.y = 0,
+            (LEFT, _) => self.x = max_x,
+            (RIGHT, _) => self.x = 0,
+            _ => {}
+        }
+
+        // Coming out of a mirrored seam flips the position along the seam.
+        // The direction through the seam stays the same.
+        if edge_mode == EdgeMode::Mirrored
+        {
+            match self.direction
             {
-                let mut new_left = self.left - self.size;
-                if new_left < 0
-                {
-                    new_left = self.screen_width - self.size;
-                }
-                self.left = new_left;
+                UP | DOWN => self.x = max_x - self.x,
+                LEFT | RIGHT => self.y = max_y - self.y,
+                _ => {}
             }
-            RIGHT =>
-            {
-                let mut new_left = self.left + self.size;
-                let new_right = new_left + self.size;
-                if new_right > self.screen_width
-                {
-                    new_left = 0;
-                }
-                self.left = new_left;
-            }
-            _ => {}
         }
     }
 
-    pub(crate) fn set_direction(&mut self, direction: Direction)
+    /// Returns the mode of the edge the next step would cross,
+    /// or `None` if the cell stays on the board.
+    pub(crate) fn get_crossed_edge(&self) -> Option<EdgeMode>
     {
-        self.direction = direction;
+        let crossing = match self.direction
+        {
+            UP => self.y == 0,
+            DOWN => self.y == self.board_height - 1,
+            LEFT => self.x == 0,
+            RIGHT => self.x == self.board_width - 1,
+            _ => false,
+        };
+
+        if !crossing
+        {
+            return None;
+        }
+
+        match self.direction
+        {
+            UP | DOWN => Some(self.topology.top_bottom),
+            _ => Some(self.topology.left_right),
+        }
     }
 
-    pub(crate) fn get_direction(&self) -> &Direction
+    /// Moves the cell onto another one's position, keeping its direction.
+    pub(crate) fn move_to(&mut self, target: &Cell)
     {
-        &self.direction
+        self.x = target.x;
+        self.y = target.y;
     }
 
-    pub(crate) fn get_top(&self) -> i64
+    pub(crate) fn set_direction(&mut self, direction: Direction)
     {
-        self.top
+        self.direction = direction;
     }
 
-    pub(crate) fn get_bottom(&self) -> i64
+    pub(crate) fn get_direction(&self) -> &Direction
     {
-        self.top + self.size
+        &self.direction
     }
 
-    pub(crate) fn get_left(&self) -> i64
+    pub(crate) fn get_x(&self) -> i64
     {
-        self.left
+        self.x
     }
 
-    pub(crate) fn get_right(&self) -> i64
+    pub(crate) fn get_y(&self) -> i64
     {
-        self.left + self.size
+        self.y
     }
 
     pub(crate) fn get_color(&self) -> Color
@@ -128,7 +239,7 @@ impl Cell
 
     pub(crate) fn overlap(&self, other: &Cell) -> bool
     {
-        self.left == other.left && self.top == other.top
+        self.x == other.x && self.y == other.y
     }
 
     pub(crate) fn set_color(&mut self, new_color: Color)
@@ -136,4 +247,3 @@ impl Cell
         self.color = new_color
     }
 }
-
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub(crate) enum Heading
+{
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Frontend-neutral player commands consumed by `Game::go`.
+/// Frontends translate their own input (keys, replays, bots) into these.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub(crate) enum Command
+{
+    /// Turns the snake of the player with the given index.
+    Turn(usize, Heading),
+    Pause,
+    /// Switches the autopilot steering the first player's snake on or off.
+    Autopilot,
+    /// Ends the game as it stands, so its score still counts. Zen games end no other way.
+    Quit,
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use crate::game::Color;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum PowerUp
+{
+    /// Halves the tick rate of the whole game.
+    SlowMotion,
+    /// Lets the snake pass through its own body.
+    Ghost,
+    /// Absorbs one fatal collision.
+    Shield,
+}
+
+pub(crate) const POWER_UPS: [PowerUp; 3] = [PowerUp::SlowMotion, PowerUp::Ghost, PowerUp::Shield];
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub(crate) struct PowerUpTypeConfig
+{
+    pub(crate) color: Color,
+    /// Relative chance of this type being picked when a power-up spawns.
+    pub(crate) weight: u32,
+    /// Ticks the effect lasts once collected.
+    pub(crate) duration: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub(crate) struct PowerUpConfig
+{
+    /// Chance per tick of a power-up appearing while none is on the board.
+    pub(crate) chance: f64,
+    /// Ticks a power-up stays on the board before it disappears.
+    pub(crate) lifetime: u64,
+    pub(crate) slow_motion: PowerUpTypeConfig,
+    pub(crate) ghost: PowerUpTypeConfig,
+    pub(crate) shield: PowerUpTypeConfig,
+}
+
+impl Default for PowerUpConfig
+{
+    /// No power-ups at all.
+    fn default() -> PowerUpConfig
+    {
+        PowerUpConfig
+        {
+            chance: 0.0,
+            lifetime: 100,
+            slow_motion: PowerUpTypeConfig { color: (255, 255, 255), weight: 1, duration: 60 },
+            ghost: PowerUpTypeConfig { color: (150, 150, 255), weight: 1, duration: 60 },
+            shield: PowerUpTypeConfig { color: (255, 160, 0), weight: 1, duration: 150 },
+        }
+    }
+}
+
+impl PowerUpConfig
+{
+    pub(crate) fn get_type(&self, power_up: PowerUp) -> &PowerUpTypeConfig
+    {
+        match power_up
+        {
+            PowerUp::SlowMotion => &self.slow_motion,
+            PowerUp::Ghost => &self.ghost,
+            PowerUp::Shield => &self.shield,
+        }
+    }
+}
+
+/// A power-up working on a snake, with the ticks it has left and lasts in total.
+#[derive(Clone, Copy)]
+pub(crate) struct Effect
+{
+    pub(crate) power_up: PowerUp,
+    pub(crate) ticks: (u64, u64),
+}
+
+/// The power-ups working on one snake, each changing the rules for as long as it lasts.
+#[derive(Default)]
+pub(crate) struct Effects
+{
+    active: Vec<Effect>,
+}
+
+impl Effects
+{
+    /// Starts an effect, or starts it over if it is already working.
+    pub(crate) fn add(&mut self, power_up: PowerUp, duration: u64)
+    {
+        self.active.retain(|effect| effect.power_up != power_up);
+        if duration > 0
+        {
+            self.active.push(Effect { power_up, ticks: (duration, duration) });
+        }
+    }
+
+    pub(crate) fn is_active(&self, power_up: PowerUp) -> bool
+    {
+        self.active.iter().any(|effect| effect.power_up == power_up)
+    }
+
+    /// Ends an effect early. Returns whether it was working.
+    pub(crate) fn use_up(&mut self, power_up: PowerUp) -> bool
+    {
+        let was_active = self.is_active(power_up);
+        self.active.retain(|effect| effect.power_up != power_up);
+        was_active
+    }
+
+    /// Ages every effect by one tick and drops the ones that have run out, returning them.
+    pub(crate) fn tick(&mut self) -> Vec<PowerUp>
+    {
+        for effect in &mut self.active
+        {
+            effect.ticks.0 = effect.ticks.0.saturating_sub(1);
+        }
+
+        let ended = self.active.iter()
+            .filter(|effect| effect.ticks.0 == 0)
+            .map(|effect| effect.power_up)
+            .collect();
+        self.active.retain(|effect| effect.ticks.0 > 0);
+        ended
+    }
+
+    pub(crate) fn clear(&mut self)
+    {
+        self.active.clear();
+    }
+
+    pub(crate) fn get_active(&self) -> &Vec<Effect>
+    {
+        &self.active
+    }
+}
@@ -0,0 +1,43 @@
+use crate::game::{FoodKind, Points};
+use crate::game::command::Heading;
+use crate::game::effect::PowerUp;
+
+/// What a snake died of.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum DeathCause
+{
+    Wall,
+    /// A solid edge of the board.
+    Edge,
+    /// Its own body.
+    Itself,
+    /// Another snake, or the head of one it swapped places with.
+    Snake,
+    /// Lethal poison.
+    Poison,
+}
+
+/// Something that happened in a game tick. Positions are board columns and rows.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum GameEvent
+{
+    FoodEaten { player: usize, kind: FoodKind, points: Points, position: (i64, i64) },
+    /// A snake got longer, by eating or, in survival mode, by time passing.
+    Grew { player: usize, length: usize },
+    /// A snake changed heading, `by_person` if a person steered it rather than a computer brain,
+    /// the autopilot or a crash.
+    Turned { player: usize, heading: Heading, by_person: bool },
+    /// A snake lost a life.
+    Died { player: usize, cause: DeathCause, position: (i64, i64) },
+    /// A campaign moved on to the level with this index, counting from zero.
+    LevelChanged { level: usize },
+    PowerUpStarted { player: usize, power_up: PowerUp },
+    /// An effect ran out, was used up or was lost with a life.
+    PowerUpEnded { player: usize, power_up: PowerUp },
+}
+
+/// Something that wants to hear about what happens in a game, such as sound effects or statistics.
+pub(crate) trait EventListener
+{
+    fn on_event(&mut self, event: &GameEvent);
+}
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use crate::game::cell::Cell;
+use crate::game::Color;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum FoodKind
+{
+    /// Worth its points and grows the snake by one cell.
+    Regular,
+    /// Like regular food, but usually worth more.
+    Bonus,
+    /// Worth its points and removes cells from the tail instead of growing.
+    Shrinking,
+    /// Kills the snake, or takes its points away if poison is not lethal.
+    Poison,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub(crate) struct FoodTypeConfig
+{
+    pub(crate) color: Color,
+    /// Relative chance of this type being picked when food spawns.
+    pub(crate) weight: u32,
+    pub(crate) points: u64,
+}
+
+/// Bonus food that shows up now and then and disappears after a while.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub(crate) struct TimedFoodConfig
+{
+    pub(crate) color: Color,
+    /// Chance per tick of it appearing while none is on the board.
+    pub(crate) chance: f64,
+    /// Ticks it stays on the board.
+    pub(crate) lifetime: u64,
+    /// Worth when it appears, dropping towards one point as it ages.
+    pub(crate) points: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub(crate) struct FoodConfig
+{
+    /// How many food items are on the board at once.
+    pub(crate) count: usize,
+    pub(crate) regular: FoodTypeConfig,
+    pub(crate) bonus: FoodTypeConfig,
+    pub(crate) shrinking: FoodTypeConfig,
+    pub(crate) poison: FoodTypeConfig,
+    /// Tail cells removed by shrinking food.
+    pub(crate) shrink_by: usize,
+    pub(crate) poison_is_lethal: bool,
+    pub(crate) timed: TimedFoodConfig,
+}
+
+impl Default for FoodConfig
+{
+    /// A single regular food item, as in the classic game.
+    fn default() -> FoodConfig
+    {
+        let disabled = |color| FoodTypeConfig { color, weight: 0, points: 0 };
+
+        FoodConfig
+        {
+            count: 1,
+            regular: FoodTypeConfig { color: (0, 255, 0), weight: 1, points: 1 },
+            bonus: disabled((255, 215, 0)),
+            shrinking: disabled((0, 191, 255)),
+            poison: disabled((160, 0, 160)),
+            shrink_by: 2,
+            poison_is_lethal: true,
+            timed: TimedFoodConfig { color: (255, 64, 64), chance: 0.0, lifetime: 0, points: 0 },
+        }
+    }
+}
+
+impl FoodConfig
+{
+    pub(crate) fn get_type(&self, kind: FoodKind) -> &FoodTypeConfig
+    {
+        match kind
+        {
+            FoodKind::Regular => &self.regular,
+            FoodKind::Bonus => &self.bonus,
+            FoodKind::Shrinking => &self.shrinking,
+            FoodKind::Poison => &self.poison,
+        }
+    }
+
+    pub(crate) fn get_type_mut(&mut self, kind: FoodKind) -> &mut FoodTypeConfig
+    {
+        match kind
+        {
+            FoodKind::Regular => &mut self.regular,
+            FoodKind::Bonus => &mut self.bonus,
+            FoodKind::Shrinking => &mut self.shrinking,
+            FoodKind::Poison => &mut self.poison,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Food
+{
+    cell: Cell,
+    kind: FoodKind,
+    points: u64,
+    /// Ticks left and total lifetime of food that expires.
+    ticks: Option<(u64, u64)>,
+}
+
+impl Food
+{
+    pub(crate) fn new(cell: Cell, kind: FoodKind, points: u64) -> Food
+    {
+        Food { cell, kind, points, ticks: None }
+    }
+
+    pub(crate) fn new_timed(cell: Cell, points: u64, lifetime: u64) -> Food
+    {
+        Food { cell, kind: FoodKind::Bonus, points, ticks: Some((lifetime, lifetime)) }
+    }
+
+    pub(crate) fn is_timed(&self) -> bool
+    {
+        self.ticks.is_some()
+    }
+
+    pub(crate) fn get_ticks(&self) -> Option<(u64, u64)>
+    {
+        self.ticks
+    }
+
+    /// Ages timed food by one tick. Returns `false` once it has expired.
+    pub(crate) fn tick(&mut self) -> bool
+    {
+        match &mut self.ticks
+        {
+            Some((left, _)) =>
+            {
+                *left = left.saturating_sub(1);
+                *left > 0
+            }
+            None => true,
+        }
+    }
+
+    /// Points for eating the food now; timed food loses value linearly with age.
+    pub(crate) fn get_points(&self) -> u64
+    {
+        match self.ticks
+        {
+            Some((left, lifetime)) => (self.points * left / lifetime.max(1)).max(1),
+            None => self.points,
+        }
+    }
+
+    pub(crate) fn get_cell(&self) -> &Cell
+    {
+        &self.cell
+    }
+
+    pub(crate) fn get_kind(&self) -> FoodKind
+    {
+        self.kind
+    }
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use crate::game::Points;
+
+/// What a player has to do to finish a level.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Goal
+{
+    /// Score at least this many points.
+    Points(Points),
+    /// Stay alive for this many seconds of play.
+    Survive(u64),
+    /// Eat every food item placed at the start. Eaten food is not replaced.
+    CollectAll,
+}
+
+impl Goal
+{
+    /// Short description for the level intro, such as `REACH 20 POINTS`.
+    pub(crate) fn describe(&self) -> String
+    {
+        match self
+        {
+            Goal::Points(points) => format!("REACH {points} POINTS"),
+            Goal::Survive(seconds) => format!("SURVIVE {seconds} SECONDS"),
+            Goal::CollectAll => "EAT ALL THE FOOD".to_string(),
+        }
+    }
+}
@@ -0,0 +1,488 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use bmp::Pixel;
+use crate::game::cell::Direction;
+use crate::game::cell::Direction::{DOWN, LEFT, RIGHT, UP};
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Tile
+{
+    Empty,
+    Wall,
+    FoodZone,
+    Portal,
+}
+
+type Position = (i64, i64);
+
+const EMPTY_PIXEL: Pixel = Pixel { r: 255, g: 255, b: 255 };
+const WALL_PIXEL: Pixel = Pixel { r: 0, g: 0, b: 0 };
+const FOOD_ZONE_PIXEL: Pixel = Pixel { r: 255, g: 255, b: 0 };
+const SNAKE_HEAD_PIXEL: Pixel = Pixel { r: 0, g: 255, b: 0 };
+const SNAKE_BODY_PIXEL: Pixel = Pixel { r: 0, g: 128, b: 0 };
+
+/// Board layout in grid coordinates (columns and rows of cells).
+///
+/// Text levels use one character per cell:
+/// `.` empty, `#` wall, `*` food spawn zone,
+/// `^` `v` `<` `>` a snake's head and the direction it starts in, one per player in reading order,
+/// `0`-`9` portals, where both cells with the same digit lead to each other.
+///
+/// Bitmap levels use one pixel per cell:
+/// white empty, black wall, yellow food spawn zone, green a snake's head, one per player in reading
+/// order, dark green an optional body pixel next to a head (that snake starts facing away from it,
+/// otherwise up), and `(255, 0, n)` with `n > 0` portals, paired by colour.
+///
+/// If a level has no food spawn zones, food may appear on any empty cell.
+pub(crate) struct Level
+{
+    width: i64,
+    height: i64,
+    tiles: Vec<Tile>,
+    starts: Vec<(Position, Direction)>,
+    portals: Vec<(Position, Position)>,
+}
+
+impl Level
+{
+    /// An empty board with the snakes spread out across the middle row, facing up. On boards
+    /// too low for snakes of `length` cells to trail below the middle, they start higher up.
+    pub(crate) fn empty(width: i64, height: i64, snake_count: usize, length: i64) -> Level
+    {
+        let snake_count = snake_count as i64;
+        let row = (height / 2).min(height - length).max(0);
+        let starts = (1..=snake_count)
+            .map(|i| ((width * i / (snake_count + 1), row), UP))
+            .collect();
+
+        Level
+        {
+            width,
+            height,
+            tiles: vec![Tile::Empty; (width * height) as usize],
+            starts,
+            portals: vec![],
+        }
+    }
+
+    pub(crate) fn load(filename: &str, width: i64, height: i64) -> Result<Level, String>
+    {
+        if filename.to_lowercase().ends_with(".bmp")
+        {
+            return Self::load_bitmap(filename, width, height);
+        }
+
+        Self::load_text(filename, width, height)
+    }
+
+    fn load_text(filename: &str, width: i64, height: i64) -> Result<Level, String>
+    {
+        let mut file = match File::open(filename)
+        {
+            Ok(f) => f,
+            Err(e) => return Err(format!("Could not open level {filename}: {e}")),
+        };
+        let mut content = String::new();
+
+        match file.read_to_string(&mut content)
+        {
+            Ok(_) => {},
+            Err(_) => return Err(format!("Could not read level {filename}")),
+        }
+
+        Self::parse(&content, width, height)
+    }
+
+    fn parse(text: &str, width: i64, height: i64) -> Result<Level, String>
+    {
+        let rows: Vec<&str> = text.lines()
+            .map(|line| line.trim_end())
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        if rows.len() as i64 != height
+        {
+            return Err(format!("Level has {} rows, expected {}.", rows.len(), height));
+        }
+
+        let mut tiles = vec![];
+        let mut starts = vec![];
+        let mut portal_ends = vec![];
+
+        for (y, row) in rows.iter().enumerate()
+        {
+            let row_width = row.chars().count() as i64;
+            if row_width != width
+            {
+                return Err(format!("Level row {} has {} columns, expected {}.",
+                                   y + 1, row_width, width));
+            }
+
+            for (x, symbol) in row.chars().enumerate()
+            {
+                let tile = match symbol
+                {
+                    '.' => Tile::Empty,
+                    '#' => Tile::Wall,
+                    '*' => Tile::FoodZone,
+                    '0'..='9' =>
+                    {
+                        portal_ends.push((symbol as u8 - b'0', (x as i64, y as i64)));
+                        Tile::Portal
+                    }
+                    '^' | 'v' | '<' | '>' =>
+                    {
+                        let direction = match symbol
+                        {
+                            '^' => UP,
+                            'v' => DOWN,
+                            '<' => LEFT,
+                            _ => RIGHT,
+                        };
+                        starts.push(((x as i64, y as i64), direction));
+                        Tile::Empty
+                    }
+                    _ => return Err(format!("Unknown level symbol '{}' at row {}, column {}.",
+                                            symbol, y + 1, x + 1)),
+                };
+                tiles.push(tile);
+            }
+        }
+
+        if starts.is_empty()
+        {
+            return Err("Level has no snake start.".to_string());
+        }
+
+        Self::build(width, height, tiles, starts, portal_ends)
+    }
+
+    fn load_bitmap(filename: &str, width: i64, height: i64) -> Result<Level, String>
+    {
+        let image = match bmp::open(filename)
+        {
+            Ok(i) => i,
+            Err(e) => return Err(format!("Could not open level {filename}: {e}")),
+        };
+
+        let (image_width, image_height) = (image.get_width() as i64, image.get_height() as i64);
+        if image_width != width || image_height != height
+        {
+            let err_msg = format!("Level image is {}x{} pixels, but the board is {}x{} cells.",
+                                  image_width, image_height, width, height);
+            return Err(err_msg);
+        }
+
+        let mut tiles = vec![];
+        let mut heads = vec![];
+        let mut bodies = vec![];
+        let mut portal_ends = vec![];
+        let mut unknown_colours: BTreeMap<(u8, u8, u8), Position> = BTreeMap::new();
+
+        for y in 0..height
+        {
+            for x in 0..width
+            {
+                let pixel = image.get_pixel(x as u32, y as u32);
+                let tile = match pixel
+                {
+                    EMPTY_PIXEL => Tile::Empty,
+                    WALL_PIXEL => Tile::Wall,
+                    FOOD_ZONE_PIXEL => Tile::FoodZone,
+                    SNAKE_HEAD_PIXEL =>
+                    {
+                        heads.push((x, y));
+                        Tile::Empty
+                    }
+                    SNAKE_BODY_PIXEL =>
+                    {
+                        bodies.push((x, y));
+                        Tile::Empty
+                    }
+                    Pixel { r: 255, g: 0, b } if b > 0 =>
+                    {
+                        portal_ends.push((b, (x, y)));
+                        Tile::Portal
+                    }
+                    _ =>
+                    {
+                        unknown_colours.entry((pixel.r, pixel.g, pixel.b)).or_insert((x, y));
+                        Tile::Empty
+                    }
+                };
+                tiles.push(tile);
+            }
+        }
+
+        if !unknown_colours.is_empty()
+        {
+            let colours: Vec<String> = unknown_colours.iter()
+                .map(|((r, g, b), (x, y))| format!("({r}, {g}, {b}) first at ({x}, {y})"))
+                .collect();
+            return Err(format!("Level has unknown colours: {}.", colours.join(", ")));
+        }
+
+        if heads.is_empty()
+        {
+            return Err("Level has no snake head pixel.".to_string());
+        }
+
+        let starts = Self::face_heads(&heads, &bodies)?;
+        Self::build(width, height, tiles, starts, portal_ends)
+    }
+
+    /// Turns each snake head away from the body pixel next to it, or up if it has none.
+    /// Every body pixel has to belong to exactly one head.
+    fn face_heads(heads: &[Position], bodies: &[Position])
+        -> Result<Vec<(Position, Direction)>, String>
+    {
+        let direction_from = |(x, y): Position, (body_x, body_y): Position|
+            match (body_x - x, body_y - y)
+        {
+            (0, 1) => Some(UP),
+            (0, -1) => Some(DOWN),
+            (1, 0) => Some(LEFT),
+            (-1, 0) => Some(RIGHT),
+            _ => None,
+        };
+
+        for &body in bodies
+        {
+            let owners = heads.iter().filter(|head| direction_from(**head, body).is_some()).count();
+            if owners != 1
+            {
+                return Err(format!("The snake body pixel at ({}, {}) has to touch exactly one \
+                                    head pixel.", body.0, body.1));
+            }
+        }
+
+        let mut starts = vec![];
+        for &head in heads
+        {
+            let directions: Vec<Direction> = bodies.iter()
+                .filter_map(|body| direction_from(head, *body))
+                .collect();
+            let direction = match directions[..]
+            {
+                [] => UP,
+                [direction] => direction,
+                _ => return Err(format!("The snake head pixel at ({}, {}) touches more than one \
+                                         body pixel.", head.0, head.1)),
+            };
+            starts.push((head, direction));
+        }
+
+        Ok(starts)
+    }
+
+    /// Pairs up portal ends with the same id and assembles the level.
+    fn build(width: i64, height: i64, tiles: Vec<Tile>, starts: Vec<(Position, Direction)>,
+             portal_ends: Vec<(u8, Position)>) -> Result<Level, String>
+    {
+        let mut ends_by_id: BTreeMap<u8, Vec<Position>> = BTreeMap::new();
+        for (id, position) in portal_ends
+        {
+            ends_by_id.entry(id).or_default().push(position);
+        }
+
+        let mut portals = vec![];
+        for (id, ends) in ends_by_id
+        {
+            if ends.len() != 2
+            {
+                return Err(format!("Portal {} has {} ends, expected 2.", id, ends.len()));
+            }
+            portals.push((ends[0], ends[1]));
+        }
+
+        let level = Level
+        {
+            width,
+            height,
+            tiles,
+            starts,
+            portals,
+        };
+
+        Ok(level)
+    }
+
+    pub(crate) fn get_tile(&self, x: i64, y: i64) -> Tile
+    {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height
+        {
+            return Tile::Empty;
+        }
+        self.tiles[(y * self.width + x) as usize]
+    }
+
+    pub(crate) fn get_starts(&self) -> &Vec<(Position, Direction)>
+    {
+        &self.starts
+    }
+
+    pub(crate) fn get_portals(&self) -> &Vec<(Position, Position)>
+    {
+        &self.portals
+    }
+
+    fn positions_of(&self, tile: Tile) -> Vec<Position>
+    {
+        (0..self.tiles.len() as i64)
+            .filter(|i| self.tiles[*i as usize] == tile)
+            .map(|i| (i % self.width, i / self.width))
+            .collect()
+    }
+
+    pub(crate) fn get_walls(&self) -> Vec<Position>
+    {
+        self.positions_of(Tile::Wall)
+    }
+
+    /// Cells a snake can fill: all but walls and portals.
+    pub(crate) fn get_open_positions(&self) -> Vec<Position>
+    {
+        let mut positions = self.positions_of(Tile::Empty);
+        positions.extend(self.positions_of(Tile::FoodZone));
+        positions
+    }
+
+    /// Cells food may appear on: the spawn zones, or every empty cell if there are none.
+    pub(crate) fn get_spawn_positions(&self) -> Vec<Position>
+    {
+        let zones = self.positions_of(Tile::FoodZone);
+        if !zones.is_empty()
+        {
+            return zones;
+        }
+        self.positions_of(Tile::Empty)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::game::command::Heading;
+
+    /// Saves a 4x3 bitmap level, white but for `pixels`, under a name taken from `name`.
+    fn save_pixels(name: &str, pixels: &[(u32, u32, Pixel)]) -> String
+    {
+        let mut image = bmp::Image::new(4, 3);
+        for (x, y) in image.coordinates()
+        {
+            image.set_pixel(x, y, EMPTY_PIXEL);
+        }
+        for (x, y, pixel) in pixels
+        {
+            image.set_pixel(*x, *y, *pixel);
+        }
+
+        let path = std::env::temp_dir().join(format!("supersnake_{name}_level.bmp"));
+        image.save(&path).unwrap();
+        path.display().to_string()
+    }
+
+    fn load_pixels(name: &str, pixels: &[(u32, u32, Pixel)]) -> Result<Level, String>
+    {
+        Level::load(&save_pixels(name, pixels), 4, 3)
+    }
+
+    fn get_start_headings(level: &Level) -> Vec<(Position, Option<Heading>)>
+    {
+        level.get_starts().iter()
+            .map(|(position, direction)| (*position, direction.to_heading()))
+            .collect()
+    }
+
+    #[test]
+    fn text_levels_place_walls_food_zones_and_starts()
+    {
+        let level = Level::parse("#..>\n.*..\n^..#\n", 4, 3).unwrap();
+
+        assert_eq!(level.get_walls(), vec![(0, 0), (3, 2)]);
+        assert_eq!(level.get_spawn_positions(), vec![(1, 1)]);
+        assert_eq!(get_start_headings(&level), vec![((3, 0), Some(Heading::Right)),
+                                                    ((0, 2), Some(Heading::Up))]);
+    }
+
+    #[test]
+    fn text_levels_must_fit_the_board()
+    {
+        assert_eq!(Level::parse("....\n..^.\n", 4, 3).err(),
+                   Some("Level has 2 rows, expected 3.".to_string()));
+        assert_eq!(Level::parse("....\n..^\n....\n", 4, 3).err(),
+                   Some("Level row 2 has 3 columns, expected 4.".to_string()));
+    }
+
+    #[test]
+    fn text_levels_need_known_symbols_and_a_start()
+    {
+        assert_eq!(Level::parse("....\n..^x\n....\n", 4, 3).err(),
+                   Some("Unknown level symbol 'x' at row 2, column 4.".to_string()));
+        assert_eq!(Level::parse("....\n....\n....\n", 4, 3).err(),
+                   Some("Level has no snake start.".to_string()));
+    }
+
+    #[test]
+    fn bitmap_levels_place_walls_food_zones_and_portals()
+    {
+        let portal = Pixel { r: 255, g: 0, b: 7 };
+        let pixels = [(0, 0, WALL_PIXEL), (1, 1, FOOD_ZONE_PIXEL), (2, 0, portal), (3, 2, portal),
+                      (0, 2, SNAKE_HEAD_PIXEL)];
+        let level = load_pixels("tiles", &pixels).unwrap();
+
+        assert_eq!(level.get_walls(), vec![(0, 0)]);
+        assert_eq!(level.get_spawn_positions(), vec![(1, 1)]);
+        assert_eq!(level.get_portals(), &vec![((2, 0), (3, 2))]);
+        assert_eq!(get_start_headings(&level), vec![((0, 2), Some(Heading::Up))]);
+    }
+
+    #[test]
+    fn bitmap_levels_reject_unknown_colours_and_other_sizes()
+    {
+        let unknown = load_pixels("unknown_colour", &[(0, 0, SNAKE_HEAD_PIXEL),
+                                                      (2, 1, Pixel { r: 1, g: 2, b: 3 })]);
+        assert_eq!(unknown.err(),
+                   Some("Level has unknown colours: (1, 2, 3) first at (2, 1).".to_string()));
+
+        let filename = save_pixels("other_size", &[(0, 0, SNAKE_HEAD_PIXEL)]);
+        assert_eq!(Level::load(&filename, 5, 3).err(),
+                   Some("Level image is 4x3 pixels, but the board is 5x3 cells.".to_string()));
+    }
+
+    #[test]
+    fn levels_need_a_head_and_both_ends_of_every_portal()
+    {
+        assert_eq!(load_pixels("no_head", &[]).err(),
+                   Some("Level has no snake head pixel.".to_string()));
+        assert_eq!(Level::parse("1...\n..^.\n....\n", 4, 3).err(),
+                   Some("Portal 1 has 1 ends, expected 2.".to_string()));
+    }
+
+    #[test]
+    fn bitmap_levels_start_a_snake_at_every_head_pixel()
+    {
+        let level = load_pixels("two_heads", &[(3, 2, SNAKE_HEAD_PIXEL), (0, 0, SNAKE_HEAD_PIXEL),
+                                              (1, 0, SNAKE_BODY_PIXEL)]).unwrap();
+
+        assert_eq!(get_start_headings(&level), vec![((0, 0), Some(Heading::Left)),
+                                                    ((3, 2), Some(Heading::Up))]);
+    }
+
+    #[test]
+    fn bitmap_body_pixels_belong_to_one_head()
+    {
+        let shared = load_pixels("shared_body", &[(0, 0, SNAKE_HEAD_PIXEL), (2, 0, SNAKE_HEAD_PIXEL),
+                                                  (1, 0, SNAKE_BODY_PIXEL)]);
+        assert!(shared.is_err());
+
+        let stray = load_pixels("stray_body", &[(0, 0, SNAKE_HEAD_PIXEL), (3, 2, SNAKE_BODY_PIXEL)]);
+        assert!(stray.is_err());
+
+        let two_bodies = load_pixels("two_bodies", &[(1, 1, SNAKE_HEAD_PIXEL), (0, 1, SNAKE_BODY_PIXEL),
+                                                     (2, 1, SNAKE_BODY_PIXEL)]);
+        assert!(two_bodies.is_err());
+    }
+}
@@ -1,94 +1,280 @@
-use crate::game::cell::Cell;
+use crate::game::autopilot::Cycle;
+use crate::game::ai::HEADINGS;
+use crate::game::cell::{Direction, Grid};
+use crate::game::command::{Command, Heading};
+use crate::game::effect::{PowerUp, POWER_UPS};
+use crate::game::food::Food;
+use crate::game::level::{Level, Tile};
+use crate::game::player::{Controller, Player};
 use crate::game::snake::Snake;
+pub(crate) use crate::game::ai::Difficulty;
+pub(crate) use crate::game::cell::Cell;
+pub(crate) use crate::game::effect::PowerUpConfig;
+pub(crate) use crate::game::food::{FoodConfig, FoodKind};
+pub(crate) use crate::game::goal::Goal;
+pub(crate) use crate::game::mode::Mode;
+pub(crate) use crate::game::speed::SpeedConfig;
+pub(crate) use crate::game::topology::{EdgeMode, Topology};
 
+mod ai;
+mod autopilot;
 mod cell;
+pub(crate) mod command;
+pub(crate) mod event;
+mod effect;
+mod food;
+mod goal;
+mod level;
+mod mode;
+mod player;
 mod snake;
+mod speed;
+mod topology;
 
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
 use crate::config::Config;
 use crate::game::cell::Direction::STOP;
+use crate::game::event::{DeathCause, GameEvent};
 
 pub(crate) type Color = (u8, u8, u8);
-type Input = minifb::Key;
-type Points = u64;
+pub(crate) type Points = u64;
+
+/// Colours given to portal pairs in turn, so linked ends look alike.
+const PORTAL_COLORS: [Color; 4] = [(255, 0, 255), (0, 255, 255), (255, 128, 0), (128, 0, 255)];
+
+/// Cells a crash cuts off the tail of a snake in zen mode.
+const ZEN_CRASH_CELLS: usize = 5;
+
+/// Head and body colours given to computer-controlled snakes in turn.
+const OPPONENT_COLORS: [(Color, Color); 3] =
+[
+    ((255, 255, 255), (192, 192, 192)),
+    ((255, 96, 96), (192, 0, 0)),
+    ((96, 160, 255), (0, 64, 192)),
+];
 
 pub struct Game
 {
-    screen_width: i64,
-    screen_height: i64,
-    cell_size: i64,
-    snake_color: Color,
+    grid: Grid,
     background_color: Color,
+    food_config: FoodConfig,
 
-    snake: Snake,
-    point_cell: Cell,
-    points: Points,
-    pace: u64,
+    level: Level,
+    wall_cells: Vec<Cell>,
+    portals: Vec<(Cell, Cell)>,
+    players: Vec<Player>,
+    foods: Vec<Food>,
+    power_up_config: PowerUpConfig,
+    /// Power-ups waiting on the board, with the ticks they have left there.
+    power_ups: Vec<(Cell, PowerUp, u64)>,
+    speed_config: SpeedConfig,
+    /// Ticks per second, growing with the best score.
+    speed: f64,
     running: bool,
+    winner: Option<usize>,
+    /// Whether the game ended because the snakes left no room for more food.
+    board_cleared: bool,
+    /// What ends the game as won, besides being the last snake standing.
+    goal: Option<Goal>,
+    goal_reached: bool,
+    /// Seconds played so far, counted in ticks at the speed each tick ran at.
+    play_seconds: f64,
+    /// Ticks played so far.
+    ticks: u64,
+    mode: Mode,
+    /// Whether a time attack ended because its time ran out.
+    time_up: bool,
+    paused: bool,
+    autopilot: bool,
+    /// Whether the autopilot steered at any point, which keeps the run off the high score table.
+    autopilot_used: bool,
+    /// Paths the autopilot plans along on boards without walls or portals.
+    cycles: Vec<Cycle>,
+    seed: u64,
+    rng: StdRng,
+    /// What happened in the last tick.
+    events: Vec<GameEvent>,
 }
 
 impl Game
 {
     pub(crate) fn new(config: &Config) -> Result<Game, String>
     {
-        let screen_width = config.screen_width;
-        let screen_height = config.screen_height;
-        let cell_size = config.cell_size;
+        let (width, height) = config.get_board_size();
         let initial_cell_count = config.initial_cell_count;
+        let topology = config.topology;
 
-        let head_color = config.head_color;
-        let snake_color = config.snake_color;
         let background_color = config.background_color;
 
-        let snake = Snake::new
-        (
-            screen_width,
-            screen_height,
-            initial_cell_count,
-            cell_size,
-            head_color,
-            snake_color
-        );
+        let mut setups = vec![(config.head_color, config.snake_color, Controller::Human)];
+        if let Some(second) = &config.second_player
+        {
+            setups.push((second.head_color, second.snake_color, Controller::Human));
+        }
+        for (i, difficulty) in config.opponents.iter().enumerate()
+        {
+            let (head_color, snake_color) = OPPONENT_COLORS[i % OPPONENT_COLORS.len()];
+            setups.push((head_color, snake_color, Controller::Computer(*difficulty)));
+        }
+
+        if width < 2 || height < 2
+        {
+            let err_msg = format!("Bad board size {width}x{height}, it needs at least 2x2 cells.");
+            return Err(err_msg);
+        }
+
+        if !(config.speed.start > 0.0 && config.speed.rate >= 0.0)
+        {
+            return Err("The start speed has to be positive and the speed rate not negative.".to_string());
+        }
+
+        if let Mode::TimeAttack(0) | Mode::Survival(0) = config.mode
+        {
+            return Err("The time limit and growth interval of a mode have to be positive.".to_string());
+        }
+
+        let grid = Grid
+        {
+            width,
+            height,
+            topology,
+        };
+
+        let level = match &config.level
+        {
+            Some(filename) => Level::load(filename, grid.get_width(), grid.get_height())?,
+            None => Level::empty(grid.get_width(), grid.get_height(), setups.len(),
+                                 initial_cell_count),
+        };
+
+        let wall_cells = level.get_walls().into_iter()
+            .map(|(x, y)| grid.make_cell(x, y, STOP, config.wall_color))
+            .collect();
+
+        let portals = level.get_portals().iter().enumerate()
+            .map(|(i, ((ax, ay), (bx, by)))|
+            {
+                let color = PORTAL_COLORS[i % PORTAL_COLORS.len()];
+                (grid.make_cell(*ax, *ay, STOP, color), grid.make_cell(*bx, *by, STOP, color))
+            })
+            .collect();
+
+        let starts = level.get_starts();
+        if starts.len() < setups.len()
+        {
+            let err_msg = format!("The level has {} snake starts, but there are {} players.",
+                                  starts.len(), setups.len());
+            return Err(err_msg);
+        }
+
+        let players: Vec<Player> = setups.iter().zip(starts)
+            .map(|((head_color, snake_color, controller), ((x, y), direction))|
+            {
+                let head = grid.make_cell(*x, *y, *direction, *head_color);
+                Player::new(head, *snake_color, initial_cell_count, config.lives.max(1),
+                            *controller)
+            })
+            .collect();
 
-        let conditions = screen_width % cell_size == 0 && screen_height % cell_size == 0;
-        if !conditions
+        let snake_cells = Self::collect_snake_cells(&players);
+        if snake_cells.iter().any(|cell| Self::is_wall(&level, &grid, cell))
         {
-            let error_message = format!
-            (
-                "Bad dimensions. \n\
-                 screen_width: {}\n\
-                screen_height: {}\n\
-                cell_size: {}\n",
-            screen_width, screen_height, cell_size)
-                .to_string();
+            return Err("A snake starts on a wall.".to_string());
+        }
+
+        // Without a configured seed pick a fresh one, so the run can still be reproduced.
+        let seed = config.seed.unwrap_or_else(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
 
-            return Err(error_message);
+        let food_config = config.food.clone();
+        let total_weight: u32 = [FoodKind::Regular, FoodKind::Bonus, FoodKind::Shrinking,
+                                 FoodKind::Poison].iter()
+            .map(|kind| food_config.get_type(*kind).weight)
+            .sum();
+        if total_weight == 0
+        {
+            return Err("At least one food type needs a spawn weight above zero.".to_string());
+        }
+        if food_config.count == 0
+        {
+            return Err("The food count needs to be at least one.".to_string());
         }
 
-        let point_cell = Self::make_random_cell(snake.get_cells(), screen_width,
-                                                screen_height, cell_size, snake_color,
-        None);
+        let cycles = match level.get_walls().is_empty() && level.get_portals().is_empty()
+        {
+            true => Cycle::all(&grid),
+            false => vec![],
+        };
+
+        let mut foods = vec![];
+        for _ in 0..food_config.count
+        {
+            let food = Self::make_food(&mut rng, &grid, &level, &food_config,
+                                       &snake_cells, &foods);
+            foods.extend(food);
+        }
 
         let game = Game
         {
-            screen_width,
-            screen_height,
-            cell_size,
-            snake_color,
+            grid,
             background_color,
-            snake,
-            point_cell,
-            points: 0,
-            pace: 0,
+            food_config,
+            level,
+            wall_cells,
+            portals,
+            players,
+            foods,
+            power_up_config: config.power_ups.clone(),
+            power_ups: vec![],
+            speed: config.speed.ticks_per_second(0),
+            speed_config: config.speed.clone(),
             running: true,
+            winner: None,
+            board_cleared: false,
+            goal: config.goal,
+            goal_reached: false,
+            play_seconds: 0.0,
+            ticks: 0,
+            mode: config.mode,
+            time_up: false,
+            paused: false,
+            autopilot: config.autopilot,
+            autopilot_used: config.autopilot,
+            cycles,
+            seed,
+            rng,
+            events: vec![],
         };
 
         Ok(game)
     }
 
-    pub(crate) fn get_snake_cells(&self) -> &Vec<Cell>
+    /// Cells of every snake still in the game.
+    fn collect_snake_cells(players: &[Player]) -> Vec<Cell>
+    {
+        players.iter()
+            .filter(|player| player.is_alive())
+            .flat_map(|player| player.get_snake().get_cells().iter().cloned())
+            .collect()
+    }
+
+    pub(crate) fn get_snake_cells(&self) -> Vec<Cell>
+    {
+        Self::collect_snake_cells(&self.players)
+    }
+
+    pub(crate) fn get_wall_cells(&self) -> &Vec<Cell>
+    {
+        &self.wall_cells
+    }
+
+    pub(crate) fn get_portal_cells(&self) -> Vec<Cell>
     {
-        self.snake.get_cells()
+        self.portals.iter()
+            .flat_map(|(a, b)| [a.clone(), b.clone()])
+            .collect()
     }
 
     pub(crate) fn get_background_color(&self) -> Color
@@ -96,61 +282,364 @@ impl Game
         self.background_color
     }
 
-    fn make_random_cell(snake_cells: &Vec<Cell>, screen_width: i64,
-                        screen_height: i64, cell_size: i64, cell_color: Color,
-                        existing_point_cell: Option<&Cell>) -> Cell
+    pub(crate) fn get_topology(&self) -> Topology
     {
-        let max_iteration_count = 10;
-        let mut rng = rand::thread_rng();
+        self.grid.topology
+    }
+
+    fn is_wall(level: &Level, grid: &Grid, cell: &Cell) -> bool
+    {
+        let (x, y) = grid.position_of(cell);
+        level.get_tile(x, y) == Tile::Wall
+    }
+
+    /// Returns where heads will come out if their next step crosses a seam.
+    pub(crate) fn get_exit_cells(&self) -> Vec<Cell>
+    {
+        self.players.iter()
+            .filter(|player| player.is_alive())
+            .map(|player| player.get_snake().get_head())
+            .filter(|head| matches!(head.get_crossed_edge(),
+                                    Some(EdgeMode::Wrap) | Some(EdgeMode::Mirrored)))
+            .map(|head|
+            {
+                let mut exit = head.clone();
+                exit.step();
+                exit
+            })
+            .collect()
+    }
+
+    pub fn get_seed(&self) -> u64
+    {
+        self.seed
+    }
 
-        'outer_loop: for _ in 0..max_iteration_count
+    /// Cells taken by snakes and power-ups, where no food or power-up may appear.
+    fn get_occupied_cells(&self) -> Vec<Cell>
+    {
+        let mut cells = self.get_snake_cells();
+        cells.extend(self.power_ups.iter().map(|(cell, _, _)| cell.clone()));
+        cells
+    }
+
+    /// Spawn positions not taken by food or any of `occupied_cells`.
+    fn get_free_positions(grid: &Grid, level: &Level, occupied_cells: &[Cell], foods: &[Food])
+        -> Vec<(i64, i64)>
+    {
+        Self::get_untaken(grid, level.get_spawn_positions(), occupied_cells, foods)
+    }
+
+    /// Those of `positions` that no cell in `occupied_cells` and no food lies on.
+    fn get_untaken(grid: &Grid, positions: Vec<(i64, i64)>, occupied_cells: &[Cell], foods: &[Food])
+        -> Vec<(i64, i64)>
+    {
+        let mut is_taken = vec![false; (grid.get_width() * grid.get_height()) as usize];
+        let taken_cells = occupied_cells.iter().chain(foods.iter().map(|food| food.get_cell()));
+        for cell in taken_cells
         {
-            let r = rng.gen_range(0..screen_width / cell_size);
-            let left = r * cell_size;
+            let (x, y) = grid.position_of(cell);
+            is_taken[(y * grid.get_width() + x) as usize] = true;
+        }
+
+        positions.into_iter()
+            .filter(|(x, y)| !is_taken[(y * grid.get_width() + x) as usize])
+            .collect()
+    }
+
+    /// A cell on a random free spawn position, or `None` if there is no room left.
+    fn make_random_cell(rng: &mut StdRng, grid: &Grid, level: &Level, occupied_cells: &[Cell],
+                        cell_color: Color, foods: &[Food]) -> Option<Cell>
+    {
+        let free_positions = Self::get_free_positions(grid, level, occupied_cells, foods);
+        if free_positions.is_empty()
+        {
+            return None;
+        }
+
+        let (x, y) = free_positions[rng.gen_range(0..free_positions.len())];
+        Some(grid.make_cell(x, y, STOP, cell_color))
+    }
+
+    /// Picks a food type by its spawn weight and places it on a free cell,
+    /// or returns `None` if there is no room left.
+    fn make_food(rng: &mut StdRng, grid: &Grid, level: &Level, food_config: &FoodConfig,
+                 occupied_cells: &[Cell], foods: &[Food]) -> Option<Food>
+    {
+        let kinds = [FoodKind::Regular, FoodKind::Bonus, FoodKind::Shrinking, FoodKind::Poison];
+        let weights = kinds.iter().map(|kind| food_config.get_type(*kind).weight);
+
+        // Game::new makes sure at least one weight is above zero.
+        let distribution = WeightedIndex::new(weights).unwrap();
+        let kind = kinds[distribution.sample(rng)];
 
-            let r = rng.gen_range(0..screen_height / cell_size);
-            let top = r * cell_size;
+        let food_type = food_config.get_type(kind);
+        let cell = Self::make_random_cell(rng, grid, level, occupied_cells, food_type.color, foods)?;
+        Some(Food::new(cell, kind, food_type.points))
+    }
 
-            let new_cell = Cell::new(left, top, cell_size, STOP, screen_width,
-                                 screen_height, cell_color);
+    /// Puts food back on the board up to the configured count, as far as there is room.
+    fn top_up_food(&mut self)
+    {
+        if !self.is_food_replaced()
+        {
+            return;
+        }
 
-            if let Some(cell) = existing_point_cell
+        let occupied_cells = self.get_occupied_cells();
+        let mut food_count = self.foods.iter().filter(|food| !food.is_timed()).count();
+
+        while food_count < self.food_config.count
+        {
+            let food = Self::make_food(&mut self.rng, &self.grid, &self.level, &self.food_config,
+                                       &occupied_cells, &self.foods);
+            match food
             {
-                if new_cell.overlap(cell)
-                {continue;}
+                Some(f) => self.foods.push(f),
+                None => break,
             }
+            food_count += 1;
+        }
+    }
+
+    /// Whether eaten food comes back, which it doesn't when the goal is to eat all of it.
+    fn is_food_replaced(&self) -> bool
+    {
+        self.goal != Some(Goal::CollectAll)
+    }
+
+    fn is_goal_met(&self) -> bool
+    {
+        match self.goal
+        {
+            Some(Goal::Points(points)) => self.get_points() >= points,
+            Some(Goal::Survive(seconds)) => self.play_seconds >= seconds as f64,
+            Some(Goal::CollectAll) => !self.foods.iter()
+                .any(|food| !food.is_timed() && food.get_kind() != FoodKind::Poison),
+            None => false,
+        }
+    }
+
+    /// Whether the snakes have filled the board: nothing is left to eat and no open cell is left,
+    /// food zone or not. Survival snakes grow without eating, so there food left lying around
+    /// does not count.
+    fn is_board_full(&self) -> bool
+    {
+        if let Mode::Survival(_) = self.mode
+        {
+            return Self::get_untaken(&self.grid, self.level.get_open_positions(),
+                                     &self.get_snake_cells(), &[]).is_empty();
+        }
+
+        let has_food = self.foods.iter().any(|food| food.get_kind() != FoodKind::Poison);
+        !has_food && Self::get_untaken(&self.grid, self.level.get_open_positions(),
+                                       &self.get_snake_cells(), &self.foods).is_empty()
+    }
+
+    /// Ages timed food, drops what has expired and now and then spawns a new one.
+    fn update_timed_food(&mut self)
+    {
+        self.foods.retain_mut(|food| food.tick());
+
+        let timed = self.food_config.timed;
+        let has_timed_food = self.foods.iter().any(|food| food.is_timed());
+        if has_timed_food || timed.chance <= 0.0 || timed.lifetime == 0
+        {
+            return;
+        }
+
+        if self.rng.gen_bool(timed.chance.min(1.0))
+        {
+            let occupied_cells = self.get_occupied_cells();
+            let cell = Self::make_random_cell(&mut self.rng, &self.grid, &self.level,
+                                              &occupied_cells, timed.color, &self.foods);
+            if let Some(cell) = cell
+            {
+                self.foods.push(Food::new_timed(cell, timed.points, timed.lifetime));
+            }
+        }
+    }
+
+    /// Ages the power-ups on the board, drops what has expired and now and then spawns a new one.
+    fn update_power_ups(&mut self)
+    {
+        for (_, _, ticks) in &mut self.power_ups
+        {
+            *ticks = ticks.saturating_sub(1);
+        }
+        self.power_ups.retain(|(_, _, ticks)| *ticks > 0);
+
+        let config = &self.power_up_config;
+        if !self.power_ups.is_empty() || config.chance <= 0.0 || config.lifetime == 0
+        {
+            return;
+        }
 
-            '_inner_loop: for cell in snake_cells
+        // Fails when no power-up has a spawn weight above zero.
+        let weights = POWER_UPS.map(|power_up| config.get_type(power_up).weight);
+        let distribution = match WeightedIndex::new(weights)
+        {
+            Ok(d) => d,
+            Err(_) => return,
+        };
+
+        if self.rng.gen_bool(config.chance.min(1.0))
+        {
+            let power_up = POWER_UPS[distribution.sample(&mut self.rng)];
+            let color = self.power_up_config.get_type(power_up).color;
+            let occupied_cells = self.get_occupied_cells();
+            let cell = Self::make_random_cell(&mut self.rng, &self.grid, &self.level,
+                                              &occupied_cells, color, &self.foods);
+            if let Some(cell) = cell
             {
-                if cell.overlap(&new_cell)
-                    { continue 'outer_loop; }
+                self.power_ups.push((cell, power_up, self.power_up_config.lifetime));
+            }
+        }
+    }
 
-                return new_cell;
+    /// Gives each snake the effect of the power-up its head is on.
+    fn collect_power_ups(&mut self)
+    {
+        for (player_index, player) in self.players.iter_mut().enumerate()
+        {
+            if !player.is_alive()
+            {
+                continue;
             }
+
+            let head = player.get_snake().get_head();
+            let index = match self.power_ups.iter().position(|(cell, _, _)| cell.overlap(head))
+            {
+                Some(i) => i,
+                None => continue,
+            };
+
+            let (_, power_up, _) = self.power_ups.remove(index);
+            let duration = self.power_up_config.get_type(power_up).duration;
+            player.get_effects_mut().add(power_up, duration);
+            self.events.push(GameEvent::PowerUpStarted { player: player_index, power_up });
         }
+    }
+
+    pub(crate) fn get_power_up_cells(&self) -> Vec<Cell>
+    {
+        self.power_ups.iter()
+            .map(|(cell, _, _)| cell.clone())
+            .collect()
+    }
+
+    /// Colour, ticks left and total duration of every effect working on a player.
+    pub(crate) fn get_player_effects(&self, player: usize) -> Vec<(Color, u64, u64)>
+    {
+        self.players[player].get_effects().get_active().iter()
+            .map(|effect|
+            {
+                let (left, total) = effect.ticks;
+                (self.power_up_config.get_type(effect.power_up).color, left, total)
+            })
+            .collect()
+    }
 
-        panic!("Max iteration count ({}) reached.", max_iteration_count);
+    /// Ticks left and total lifetime of the timed food on the board, if there is one.
+    pub(crate) fn get_timed_food_ticks(&self) -> Option<(u64, u64)>
+    {
+        self.foods.iter().find_map(|food| food.get_ticks())
     }
 
-    pub(crate) fn get_point_cell(&self) -> &Cell
+    pub(crate) fn get_timed_food_color(&self) -> Color
     {
-        &self.point_cell
+        self.food_config.timed.color
     }
 
+    pub(crate) fn get_food_cells(&self) -> Vec<Cell>
+    {
+        self.foods.iter()
+            .map(|food| food.get_cell().clone())
+            .collect()
+    }
+
+    /// Points of the first player.
     pub fn get_points(&self) -> Points
     {
-        self.points
+        self.players[0].get_points()
+    }
+
+    /// Number of cells in the first player's snake.
+    pub(crate) fn get_length(&self) -> usize
+    {
+        self.players[0].get_snake().get_cells().len()
+    }
+
+    pub(crate) fn get_player_count(&self) -> usize
+    {
+        self.players.len()
+    }
+
+    pub(crate) fn get_player_points(&self, player: usize) -> Points
+    {
+        self.players[player].get_points()
+    }
+
+    pub(crate) fn get_player_lives(&self, player: usize) -> u32
+    {
+        self.players[player].get_lives()
+    }
+
+    pub(crate) fn get_player_color(&self, player: usize) -> Color
+    {
+        self.players[player].get_head_color()
+    }
+
+    /// The last player standing in a game with several players.
+    pub fn get_winner(&self) -> Option<usize>
+    {
+        self.winner
+    }
+
+    pub(crate) fn is_board_cleared(&self) -> bool
+    {
+        self.board_cleared
+    }
+
+    pub(crate) fn is_goal_reached(&self) -> bool
+    {
+        self.goal_reached
     }
 
-    pub fn get_pace(&self) -> u64
+    pub(crate) fn is_time_up(&self) -> bool
     {
-        self.pace
+        self.time_up
     }
 
-    fn increase_pace(&mut self)
+    /// Whole seconds left in a time attack, rounded up.
+    pub(crate) fn get_time_left(&self) -> Option<u64>
     {
-        // quadratic function
-        self.pace = self.points * self.points;
+        match self.mode
+        {
+            Mode::TimeAttack(seconds) => Some((seconds as f64 - self.play_seconds).max(0.0).ceil() as u64),
+            _ => None,
+        }
+    }
+
+    /// Ticks per second the game should run at.
+    pub fn get_speed(&self) -> f64
+    {
+        let is_slowed = self.players.iter()
+            .any(|player| player.get_effects().is_active(PowerUp::SlowMotion));
+        match is_slowed
+        {
+            true => self.speed / 2.0,
+            false => self.speed,
+        }
+    }
+
+    fn increase_speed(&mut self)
+    {
+        let points = self.players.iter()
+            .map(|player| player.get_points())
+            .max()
+            .unwrap_or(0);
+        self.speed = self.speed_config.ticks_per_second(points);
     }
 
     pub fn is_running(&self) -> bool
@@ -158,30 +647,810 @@ impl Game
         self.running
     }
 
-    pub(crate) fn go(&mut self, input: Option<Input>)
+    pub fn is_paused(&self) -> bool
     {
-        self.snake.go(input);
+        self.paused
+    }
 
-        if self.snake.is_collecting_point(&self.point_cell)
+    /// Whether a person steers the player's snake, rather than a computer brain or the autopilot.
+    fn is_human(&self, player: usize) -> bool
+    {
+        self.players[player].get_controller() == Controller::Human && !(player == 0 && self.autopilot)
+    }
+
+    pub(crate) fn was_autopilot_used(&self) -> bool
+    {
+        self.autopilot_used
+    }
+
+    /// Lets a player eat the food its head landed on. Returns `false` if the food killed
+    /// the snake. Food that makes it grow puts it back on `old_tail`, the cell its tail left.
+    fn eat(&mut self, player_index: usize, food_index: usize, old_tail: &Cell) -> bool
+    {
+        let food = self.foods.remove(food_index);
+        let kind = food.get_kind();
+        let food_points = food.get_points();
+        self.events.push(GameEvent::FoodEaten
+        {
+            player: player_index,
+            kind,
+            points: food_points,
+            position: self.grid.position_of(food.get_cell()),
+        });
+
+        let player = &mut self.players[player_index];
+        match kind
+        {
+            FoodKind::Regular | FoodKind::Bonus =>
+            {
+                player.add_points(food_points);
+                player.get_snake_mut().grow(old_tail.clone());
+                let length = player.get_snake().get_cells().len();
+                self.events.push(GameEvent::Grew { player: player_index, length });
+            }
+            FoodKind::Shrinking =>
+            {
+                player.add_points(food_points);
+                player.get_snake_mut().shrink(self.food_config.shrink_by);
+            }
+            FoodKind::Poison =>
+            {
+                if !self.food_config.poison_is_lethal
+                {
+                    player.take_points(food_points);
+                }
+            }
+        }
+
+        // Timed food is extra, only the regular supply is topped up.
+        if !food.is_timed() && self.is_food_replaced()
         {
-            self.snake.change_head(self.point_cell.clone());
-            self.points += 1;
-            self.point_cell = Self::make_random_cell
-            (
-                self.snake.get_cells(),
-                self.screen_width,
-                self.screen_height,
-                self.cell_size,
-                self.snake_color,
-                Some(&self.point_cell)
-            );
+            let occupied_cells = self.get_occupied_cells();
+            let new_food = Self::make_food(&mut self.rng, &self.grid, &self.level,
+                                           &self.food_config, &occupied_cells, &self.foods);
+            if let Some(new_food) = new_food
+            {
+                self.foods.insert(food_index, new_food);
+            }
+        }
 
-            self.increase_pace();
+        self.increase_speed();
+
+        !(kind == FoodKind::Poison && self.food_config.poison_is_lethal)
+    }
+
+    /// Checks whether a player's head ran into its own body, another snake,
+    /// or swapped places with another head.
+    fn get_collision(&self, player_index: usize, old_heads: &[Cell]) -> Option<DeathCause>
+    {
+        let player = &self.players[player_index];
+        let snake = player.get_snake();
+        if snake.is_tangled() && !player.get_effects().is_active(PowerUp::Ghost)
+        {
+            return Some(DeathCause::Itself);
         }
 
-        if self.snake.is_tangled()
+        let head = snake.get_head();
+        for (other_index, other) in self.players.iter().enumerate()
+        {
+            if other_index == player_index || !other.is_alive()
+            {
+                continue;
+            }
+
+            let other_snake = other.get_snake();
+            if other_snake.get_cells().iter().any(|cell| cell.overlap(head))
+            {
+                return Some(DeathCause::Snake);
+            }
+
+            let other_head = other_snake.get_head();
+            let swapped = head.overlap(&old_heads[other_index])
+                && other_head.overlap(&old_heads[player_index]);
+            if swapped
+            {
+                return Some(DeathCause::Snake);
+            }
+        }
+
+        None
+    }
+
+    /// Cuts every snake that ran into itself where it hit, so in zen mode it lives on shorter.
+    fn trim_tangled_snakes(&mut self)
+    {
+        for player in self.players.iter_mut().filter(|player| player.is_alive())
+        {
+            if !player.get_effects().is_active(PowerUp::Ghost)
+            {
+                player.get_snake_mut().untangle();
+            }
+        }
+    }
+
+    /// Grows every snake that moved safely by the tail cell it just left, scoring a point for
+    /// surviving that long.
+    fn grow_snakes(&mut self, old_tails: &[Cell], deaths: &[Option<DeathCause>])
+    {
+        for (player_index, (player, tail)) in self.players.iter_mut().zip(old_tails).enumerate()
+        {
+            if player.is_alive() && deaths[player_index].is_none()
+            {
+                player.get_snake_mut().grow(tail.clone());
+                player.add_points(1);
+                let length = player.get_snake().get_cells().len();
+                self.events.push(GameEvent::Grew { player: player_index, length });
+            }
+        }
+    }
+
+    /// The board as seen by one player's snake, for computer players to plan on.
+    fn make_board(&self, player_index: usize) -> ai::Board
+    {
+        let width = self.grid.get_width();
+        let mut blocked = vec![false; (width * self.grid.get_height()) as usize];
+        let mut block = |cell: &Cell|
+        {
+            let (x, y) = self.grid.position_of(cell);
+            blocked[(y * width + x) as usize] = true;
+        };
+
+        self.wall_cells.iter().for_each(&mut block);
+        for player in self.players.iter().filter(|player| player.is_alive())
+        {
+            player.get_snake().get_cells().iter().for_each(&mut block);
+        }
+        self.foods.iter()
+            .filter(|food| food.get_kind() == FoodKind::Poison)
+            .for_each(|food| block(food.get_cell()));
+
+        let (poison, foods): (Vec<&Food>, Vec<&Food>) = self.foods.iter()
+            .partition(|food| food.get_kind() == FoodKind::Poison);
+        let positions = |foods: Vec<&Food>| foods.iter()
+            .map(|food| self.grid.position_of(food.get_cell()))
+            .collect();
+
+        let opponents = self.players.iter().enumerate()
+            .filter(|(i, player)| *i != player_index && player.is_alive())
+            .map(|(_, player)|
+            {
+                let head = player.get_snake().get_head();
+                (self.grid.position_of(head), *head.get_direction())
+            })
+            .collect();
+
+        let portals = self.portals.iter()
+            .map(|(a, b)| (self.grid.position_of(a), self.grid.position_of(b)))
+            .collect();
+
+        let snake = self.players[player_index].get_snake();
+        let head = snake.get_head();
+
+        ai::Board
+        {
+            grid: self.grid,
+            blocked,
+            portals,
+            foods: positions(foods),
+            poison: positions(poison),
+            opponents,
+            head: self.grid.position_of(head),
+            body: snake.get_cells().iter()
+                .map(|cell| self.grid.position_of(cell))
+                .collect(),
+            direction: *head.get_direction(),
+            length: snake.get_cells().len(),
+            growth_interval: match self.mode
+            {
+                Mode::Survival(interval) => Some(interval),
+                _ => None,
+            },
+            tick: self.ticks,
+        }
+    }
+
+    /// The heading a player's snake takes this tick: the next queued turn, the choice of its
+    /// computer brain or, for the first player, of the autopilot.
+    fn get_heading(&mut self, player_index: usize) -> Option<Heading>
+    {
+        if player_index == 0 && self.autopilot
+        {
+            self.players[player_index].clear_turns();
+
+            let mut board = self.make_board(player_index);
+            if !self.food_config.poison_is_lethal
+            {
+                // Better to lose some points than to get stuck behind poison lying on the cycle.
+                board.unblock_poison();
+            }
+            return autopilot::choose_heading(&board, &self.cycles);
+        }
+
+        match self.players[player_index].get_controller()
+        {
+            Controller::Human => self.players[player_index].take_turn(),
+            Controller::Computer(difficulty) =>
+            {
+                ai::choose_heading(&self.make_board(player_index), difficulty)
+            }
+        }
+    }
+
+    /// Moves a player's snake a cell on. Returns what it crashed into, if anything.
+    fn move_player(&mut self, player_index: usize, heading: Option<Heading>) -> Option<DeathCause>
+    {
+        let snake = self.players[player_index].get_snake_mut();
+        if snake.get_head().get_crossed_edge() == Some(EdgeMode::Solid)
+        {
+            return Some(DeathCause::Edge);
+        }
+
+        let old_heading = snake.get_head().get_direction().to_heading();
+        snake.go(heading);
+        let new_heading = snake.get_head().get_direction().to_heading();
+        if let (Some(heading), true) = (heading, new_heading != old_heading)
+        {
+            let by_person = self.is_human(player_index);
+            self.events.push(GameEvent::Turned { player: player_index, heading, by_person });
+        }
+
+        let snake = self.players[player_index].get_snake_mut();
+        snake.use_portals(&self.portals);
+
+        match Self::is_wall(&self.level, &self.grid, snake.get_head())
+        {
+            true => Some(DeathCause::Wall),
+            false => None,
+        }
+    }
+
+    /// Whether a crash would leave the snake alive, thanks to a shield or zen mode.
+    fn can_take_hit(&self, player_index: usize) -> bool
+    {
+        self.mode == Mode::Zen || self.players[player_index].get_effects().is_active(PowerUp::Shield)
+    }
+
+    /// Puts a snake that ran into a wall or a solid edge back to `before`, the way it was before
+    /// it moved, and moves it on towards `heading` if that way is clear, or else any other clear
+    /// way but back. The shield, or in zen mode the end of the tail, pays for the crash.
+    fn turn_from_crash(&mut self, player_index: usize, before: Snake, heading: Option<Heading>,
+                       crash: Option<DeathCause>) -> Option<DeathCause>
+    {
+        *self.players[player_index].get_snake_mut() = before;
+        if self.take_hit(player_index, crash).is_some()
+        {
+            self.cut_tail(player_index);
+        }
+
+        let head = self.players[player_index].get_snake().get_head();
+        let facing = head.get_direction().to_heading();
+        let reverse = head.get_direction().opposite().to_heading();
+        let position = self.grid.position_of(head);
+
+        let turn = heading.into_iter().chain(HEADINGS)
+            .filter(|turn| Some(*turn) != facing && Some(*turn) != reverse)
+            .find(|turn| self.grid.step_from(position, Direction::from_heading(*turn))
+                .is_some_and(|(x, y)| self.level.get_tile(x, y) != Tile::Wall));
+        let turn = match turn
+        {
+            Some(turn) => turn,
+            None => return crash,
+        };
+
+        self.players[player_index].get_snake_mut().turn_to(turn);
+        self.events.push(GameEvent::Turned { player: player_index, heading: turn, by_person: false });
+        self.move_player(player_index, None)
+    }
+
+    /// Costs a snake in zen mode the end of its tail and its effects, instead of a life.
+    fn cut_tail(&mut self, player_index: usize)
+    {
+        let player = &mut self.players[player_index];
+        for effect in player.get_effects().get_active()
+        {
+            self.events.push(GameEvent::PowerUpEnded { player: player_index, power_up: effect.power_up });
+        }
+        player.get_effects_mut().clear();
+        player.get_snake_mut().shrink(ZEN_CRASH_CELLS);
+    }
+
+    /// Lets a shield take a crash instead of the player. Returns the crash if it still kills.
+    fn take_hit(&mut self, player_index: usize, crash: Option<DeathCause>) -> Option<DeathCause>
+    {
+        let cause = crash?;
+        match self.players[player_index].absorb_hit()
+        {
+            true =>
+            {
+                self.events.push(GameEvent::PowerUpEnded { player: player_index, power_up: PowerUp::Shield });
+                None
+            }
+            false => Some(cause),
+        }
+    }
+
+    /// Costs a crashed player a life, or in zen mode just the end of its tail.
+    /// Its effects are lost either way.
+    fn crash(&mut self, player_index: usize, cause: DeathCause)
+    {
+        if self.mode == Mode::Zen
+        {
+            self.cut_tail(player_index);
+            return;
+        }
+
+        let player = &mut self.players[player_index];
+        for effect in player.get_effects().get_active()
+        {
+            self.events.push(GameEvent::PowerUpEnded { player: player_index, power_up: effect.power_up });
+        }
+
+        let position = self.grid.position_of(player.get_snake().get_head());
+        self.events.push(GameEvent::Died { player: player_index, cause, position });
+        player.lose_life();
+    }
+
+    /// What happened in the last tick, in order.
+    pub(crate) fn get_events(&self) -> &Vec<GameEvent>
+    {
+        &self.events
+    }
+
+    pub(crate) fn go(&mut self, commands: &[Command])
+    {
+        self.events.clear();
+
+        if commands.contains(&Command::Quit)
         {
             self.running = false;
+            return;
+        }
+
+        if commands.contains(&Command::Pause)
+        {
+            self.paused = !self.paused;
+            return;
+        }
+
+        if commands.contains(&Command::Autopilot)
+        {
+            self.autopilot = !self.autopilot;
+            self.autopilot_used |= self.autopilot;
+        }
+
+        if self.paused
+        {
+            return;
+        }
+
+        let player_count = self.players.len();
+        let old_heads: Vec<Cell> = self.players.iter()
+            .map(|player| player.get_snake().get_head().clone())
+            .collect();
+        let old_tails: Vec<Cell> = self.players.iter()
+            .map(|player|
+            {
+                let cells = player.get_snake().get_cells();
+                cells[cells.len() - 1].clone()
+            })
+            .collect();
+        let mut deaths: Vec<Option<DeathCause>> = vec![None; player_count];
+        self.play_seconds += 1.0 / self.get_speed();
+        self.ticks += 1;
+
+        for (player_index, player) in self.players.iter_mut().enumerate()
+        {
+            for power_up in player.get_effects_mut().tick()
+            {
+                self.events.push(GameEvent::PowerUpEnded { player: player_index, power_up });
+            }
+        }
+
+        for command in commands
+        {
+            if let Command::Turn(player_index, heading) = command
+            {
+                let player = self.players.get_mut(*player_index)
+                    .filter(|player| player.get_controller() == Controller::Human);
+                if let Some(player) = player
+                {
+                    player.queue_turn(*heading);
+                }
+            }
+        }
+
+        // Every snake decides on the same board before any of them moves.
+        let headings: Vec<Option<Heading>> = (0..player_count)
+            .map(|player_index| match self.players[player_index].is_alive()
+            {
+                true => self.get_heading(player_index),
+                false => None,
+            })
+            .collect();
+
+        for (player_index, death) in deaths.iter_mut().enumerate()
+        {
+            if self.players[player_index].is_alive()
+            {
+                let before = self.players[player_index].get_snake().clone();
+                let mut crash = self.move_player(player_index, headings[player_index]);
+                let is_bump = matches!(crash, Some(DeathCause::Edge | DeathCause::Wall));
+                if is_bump && self.can_take_hit(player_index)
+                {
+                    crash = self.turn_from_crash(player_index, before, headings[player_index], crash);
+                }
+                *death = self.take_hit(player_index, crash);
+            }
+        }
+
+        // Growing before anything is eaten keeps the old tail next to the body, however much
+        // shrinking food takes off afterwards.
+        if let Mode::Survival(interval) = self.mode
+        {
+            if self.ticks.is_multiple_of(interval)
+            {
+                self.grow_snakes(&old_tails, &deaths);
+            }
+        }
+
+        for (player_index, death) in deaths.iter_mut().enumerate()
+        {
+            if death.is_some() || !self.players[player_index].is_alive()
+            {
+                continue;
+            }
+
+            let snake = self.players[player_index].get_snake();
+            if let Some(food_index) = snake.get_collected_food(&self.foods)
+            {
+                if !self.eat(player_index, food_index, &old_tails[player_index])
+                {
+                    *death = Some(DeathCause::Poison);
+                }
+            }
+        }
+
+        self.collect_power_ups();
+
+        if self.mode == Mode::Zen
+        {
+            self.trim_tangled_snakes();
+        }
+
+        for (player_index, death) in deaths.iter_mut().enumerate()
+        {
+            if death.is_none() && self.players[player_index].is_alive()
+            {
+                let crash = self.get_collision(player_index, &old_heads);
+                *death = self.take_hit(player_index, crash);
+            }
+        }
+
+        for (player_index, death) in deaths.into_iter().enumerate()
+        {
+            if let Some(cause) = death
+            {
+                self.crash(player_index, cause);
+            }
+        }
+
+        self.top_up_food();
+        self.update_timed_food();
+        self.update_power_ups();
+
+        let alive: Vec<usize> = (0..player_count)
+            .filter(|i| self.players[*i].is_alive())
+            .collect();
+        let humans_alive = alive.iter()
+            .any(|i| self.players[*i].get_controller() == Controller::Human);
+        let humans = self.players.iter()
+            .filter(|player| player.get_controller() == Controller::Human)
+            .count();
+
+        if player_count == 1
+        {
+            self.running = humans_alive;
+        }
+        // The last snake standing only wins between people. Alone against the computer,
+        // a player plays on until they are out.
+        else if !humans_alive || (humans > 1 && alive.len() <= 1)
+        {
+            // With several snakes left, the best scorer among them wins.
+            self.running = false;
+            self.winner = alive.iter().copied()
+                .max_by_key(|i| (self.players[*i].get_points(), std::cmp::Reverse(*i)));
+        }
+
+        if let Mode::TimeAttack(seconds) = self.mode
+        {
+            if self.running && self.play_seconds >= seconds as f64
+            {
+                self.running = false;
+                self.time_up = true;
+            }
+        }
+
+        if self.running && self.is_goal_met()
+        {
+            self.running = false;
+            self.goal_reached = true;
+        }
+
+        if self.running && self.is_board_full()
+        {
+            self.running = false;
+            self.board_cleared = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn solid_game_with_shield() -> Game
+    {
+        let mut config = Config::for_board(8, 8);
+        config.seed = Some(0);
+        config.topology = Topology { left_right: EdgeMode::Solid, top_bottom: EdgeMode::Solid };
+        let mut game = Game::new(&config).unwrap();
+        game.players[0].get_effects_mut().add(PowerUp::Shield, 1000);
+        game
+    }
+
+    /// A game on a 6x6 text level, written to a temporary file named after `name`.
+    fn config_with_level(name: &str, level: &str) -> Config
+    {
+        let path = std::env::temp_dir().join(format!("supersnake_{name}_level.txt"));
+        std::fs::write(&path, level).unwrap();
+        let mut config = Config::for_board(6, 6);
+        config.level = Some(path.display().to_string());
+        config.seed = Some(0);
+        config
+    }
+
+    /// Runs the game into the wall two cells ahead of the snake on the wall level, with no food
+    /// in the way, returning the head's position.
+    fn run_into_wall(mut game: Game) -> (Game, (i64, i64))
+    {
+        game.foods.clear();
+        game.go(&[]);
+        game.go(&[]);
+        let position = game.grid.position_of(game.players[0].get_snake().get_head());
+        (game, position)
+    }
+
+    const WALL_LEVEL: &str = "......\n..#...\n......\n..^...\n......\n......\n";
+
+    /// Runs the game until the snake faces the edge it is about to hit.
+    fn run_to_edge(game: &mut Game)
+    {
+        while game.players[0].get_snake().get_head().get_crossed_edge() != Some(EdgeMode::Solid)
+        {
+            game.go(&[]);
+            assert!(game.is_running());
+        }
+    }
+
+    #[test]
+    fn shield_turns_the_snake_along_a_solid_edge()
+    {
+        let mut game = solid_game_with_shield();
+        run_to_edge(&mut game);
+
+        game.go(&[]);
+        assert!(game.is_running());
+        assert!(!game.players[0].get_effects().is_active(PowerUp::Shield));
+        assert!(game.get_events().iter().any(|event| matches!(event, GameEvent::Turned { .. })));
+        let head = game.players[0].get_snake().get_head();
+        assert!(!game.get_snake_cells().iter().skip(1).any(|cell| cell.overlap(head)));
+    }
+
+    #[test]
+    fn shield_keeps_the_heading_the_player_asked_for()
+    {
+        let mut game = solid_game_with_shield();
+        run_to_edge(&mut game);
+
+        let facing = game.players[0].get_snake().get_head().get_direction().to_heading().unwrap();
+        let turn = match facing
+        {
+            Heading::Up | Heading::Down => Heading::Left,
+            Heading::Left | Heading::Right => Heading::Up,
+        };
+        game.go(&[Command::Turn(0, turn)]);
+        assert!(game.is_running());
+        assert_eq!(game.players[0].get_snake().get_head().get_direction().to_heading(), Some(turn));
+    }
+
+    #[test]
+    fn zen_crash_costs_the_tail_instead_of_the_start()
+    {
+        let mut config = Config::for_board(16, 16);
+        config.seed = Some(0);
+        config.mode = Mode::Zen;
+        config.initial_cell_count = 8;
+        config.topology = Topology { left_right: EdgeMode::Solid, top_bottom: EdgeMode::Solid };
+        let mut game = Game::new(&config).unwrap();
+        run_to_edge(&mut game);
+        let length = game.get_length();
+
+        game.go(&[]);
+        assert!(game.is_running());
+        assert_eq!(game.get_length(), length - ZEN_CRASH_CELLS);
+        assert!(game.players[0].get_snake().get_head().get_crossed_edge() != Some(EdgeMode::Solid));
+    }
+
+    #[test]
+    fn quitting_ends_a_zen_game()
+    {
+        let mut config = Config::for_board(16, 16);
+        config.mode = Mode::Zen;
+        let mut game = Game::new(&config).unwrap();
+
+        game.go(&[]);
+        assert!(game.is_running());
+        game.go(&[Command::Quit]);
+        assert!(!game.is_running());
+    }
+
+    #[test]
+    fn reports_a_food_count_of_zero_apart_from_the_weights()
+    {
+        let mut config = Config::for_board(8, 8);
+        config.food.count = 0;
+        let err = Game::new(&config).err().unwrap();
+        assert!(err.contains("count"), "{err}");
+    }
+
+    #[test]
+    fn a_lone_player_plays_on_after_the_computer_snakes_crash()
+    {
+        let mut config = Config::for_board(12, 12);
+        config.opponents = vec![Difficulty::Greedy];
+        let mut game = Game::new(&config).unwrap();
+        game.players[1].lose_life();
+
+        game.go(&[]);
+        assert!(game.is_running());
+        assert_eq!(game.get_winner(), None);
+    }
+
+    #[test]
+    fn a_covered_food_zone_does_not_clear_the_board()
+    {
+        let config = config_with_level("food_zone", "......\n......\n..*...\n..^...\n......\n......\n");
+        let mut game = Game::new(&config).unwrap();
+
+        game.go(&[]);
+        assert_eq!(game.get_points(), 1);
+        assert!(game.is_running());
+        assert!(!game.is_board_cleared());
+    }
+
+    #[test]
+    fn shield_turns_the_snake_away_from_a_wall()
+    {
+        let config = config_with_level("shield_wall", WALL_LEVEL);
+        let mut game = Game::new(&config).unwrap();
+        game.players[0].get_effects_mut().add(PowerUp::Shield, 1000);
+        let (game, head) = run_into_wall(game);
+
+        assert!(game.is_running());
+        assert!(!game.players[0].get_effects().is_active(PowerUp::Shield));
+        assert_eq!(head, (1, 2));
+    }
+
+    #[test]
+    fn zen_wall_crash_does_not_tunnel_through_the_wall()
+    {
+        let mut config = config_with_level("zen_wall", WALL_LEVEL);
+        config.mode = Mode::Zen;
+        let (game, head) = run_into_wall(Game::new(&config).unwrap());
+
+        assert!(game.is_running());
+        assert!(game.level.get_tile(head.0, head.1) != Tile::Wall);
+        assert_eq!(head, (1, 2));
+    }
+
+    #[test]
+    fn only_turns_a_person_steers_are_marked_as_theirs()
+    {
+        let mut config = Config::for_board(8, 8);
+        config.seed = Some(0);
+        let mut game = Game::new(&config).unwrap();
+        game.go(&[Command::Turn(0, Heading::Left)]);
+        assert!(game.get_events().contains(&GameEvent::Turned { player: 0, heading: Heading::Left, by_person: true }));
+
+        config.autopilot = true;
+        let mut game = Game::new(&config).unwrap();
+        let mut turns = 0;
+        for _ in 0..50
+        {
+            game.go(&[]);
+            for event in game.get_events()
+            {
+                if let GameEvent::Turned { by_person, .. } = event
+                {
+                    assert!(!by_person);
+                    turns += 1;
+                }
+            }
+        }
+        assert!(turns > 0);
+    }
+
+        fn is_connected(game: &Game, snake: &Snake) -> bool
+    {
+        let directions = [Direction::UP, Direction::DOWN, Direction::LEFT, Direction::RIGHT];
+        snake.get_cells().windows(2).all(|pair|
+        {
+            let (from, to) = (game.grid.position_of(&pair[0]), game.grid.position_of(&pair[1]));
+            directions.iter().any(|direction| game.grid.step_from(from, *direction) == Some(to))
+        })
+    }
+
+    #[test]
+    fn survival_growth_stays_attached_after_shrinking_food()
+    {
+        let mut config = config_with_level("survival_shrink", "......\n......\n......\n..^...\n......\n......\n");
+        config.mode = Mode::Survival(1);
+        let mut game = Game::new(&config).unwrap();
+        game.food_config.shrink_by = 1;
+        game.foods = vec![Food::new(game.grid.make_cell(2, 2, Direction::UP, (0, 0, 0)), FoodKind::Shrinking, 0)];
+        game.go(&[]);
+
+        let snake = game.players[0].get_snake();
+        assert!(game.is_running());
+        assert_eq!(snake.get_cells().len(), 3);
+        assert!(is_connected(&game, snake));
+    }
+
+    #[test]
+    fn filling_the_board_wins_survival()
+    {
+        let mut config = config_with_level("survival_fill", "######\n##.###\n##^###\n##.###\n##.###\n######\n");
+        config.mode = Mode::Survival(1);
+        let mut game = Game::new(&config).unwrap();
+        game.go(&[]);
+
+        assert!(!game.is_running());
+        assert!(game.is_board_cleared());
+    }
+
+    #[test]
+    fn same_seed_and_commands_play_the_same_game()
+    {
+        let mut config = Config::for_board(12, 12);
+        config.seed = Some(42);
+        config.opponents = vec![Difficulty::Pathfinding];
+        let commands = [Command::Turn(0, Heading::Up), Command::Turn(0, Heading::Left),
+                        Command::Turn(0, Heading::Down), Command::Turn(0, Heading::Right)];
+
+        let mut games = [Game::new(&config).unwrap(), Game::new(&config).unwrap()];
+        for tick in 0..200
+        {
+            let input = match tick % 7
+            {
+                0 => vec![commands[tick / 7 % commands.len()]],
+                _ => vec![],
+            };
+
+            for game in &mut games
+            {
+                game.go(&input);
+            }
+
+            let [a, b] = &games;
+            assert_eq!(a.get_events(), b.get_events());
+            assert_eq!(a.is_running(), b.is_running());
+            assert_eq!(a.get_points(), b.get_points());
+            let positions = |game: &Game| game.get_snake_cells().iter()
+                .chain(game.get_food_cells().iter())
+                .map(|cell| game.grid.position_of(cell))
+                .collect::<Vec<_>>();
+            assert_eq!(positions(a), positions(b));
         }
     }
 }
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// The ruleset a game is played by.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Mode
+{
+    /// Eat, grow and don't crash.
+    #[default]
+    Classic,
+    /// Score as many points as possible in this many seconds of play.
+    TimeAttack(u64),
+    /// The snake grows by a cell every this many ticks, eating or not, and scores a point
+    /// each time it does. It never grows more than a cell a tick. Filling the board wins.
+    Survival(u64),
+    /// Nothing kills. Running into itself cuts the snake where it hit, and running into
+    /// anything else costs it the end of its tail. The game lasts until the player quits.
+    Zen,
+}
+
+impl Mode
+{
+    /// Name used to keep the high scores of each mode apart.
+    pub(crate) fn get_name(&self) -> &'static str
+    {
+        match self
+        {
+            Mode::Classic => "classic",
+            Mode::TimeAttack(_) => "time_attack",
+            Mode::Survival(_) => "survival",
+            Mode::Zen => "zen",
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::VecDeque;
+use crate::game::ai::Difficulty;
+use crate::game::cell::{Cell, Direction};
+use crate::game::command::Heading;
+use crate::game::effect::{Effects, PowerUp};
+use crate::game::snake::Snake;
+use crate::game::{Color, Points};
+
+/// Who steers a snake.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Controller
+{
+    Human,
+    Computer(Difficulty),
+}
+
+/// Turns a player can line up ahead of the snake.
+const MAX_QUEUED_TURNS: usize = 3;
+
+/// A snake together with everything that belongs to whoever steers it.
+pub(crate) struct Player
+{
+    snake: Snake,
+    start: Cell,
+    snake_color: Color,
+    initial_cell_count: i64,
+    points: Points,
+    lives: u32,
+    controller: Controller,
+    /// Turns waiting to be made, one per tick, oldest first.
+    queued_turns: VecDeque<Heading>,
+    effects: Effects,
+}
+
+impl Player
+{
+    /// `start` is the head cell the snake (re)spawns from, already facing its start direction.
+    pub(crate) fn new(start: Cell, snake_color: Color, initial_cell_count: i64, lives: u32,
+                      controller: Controller) -> Player
+    {
+        let snake = Snake::new(start.clone(), initial_cell_count, snake_color);
+
+        Player
+        {
+            snake,
+            start,
+            snake_color,
+            initial_cell_count,
+            points: 0,
+            lives,
+            controller,
+            queued_turns: VecDeque::new(),
+            effects: Effects::default(),
+        }
+    }
+
+    pub(crate) fn get_snake(&self) -> &Snake
+    {
+        &self.snake
+    }
+
+    pub(crate) fn get_snake_mut(&mut self) -> &mut Snake
+    {
+        &mut self.snake
+    }
+
+    pub(crate) fn get_controller(&self) -> Controller
+    {
+        self.controller
+    }
+
+    /// Lines up a turn, checked against the direction the snake will have once the turns
+    /// before it are made. Turns that would change nothing or reverse the snake are dropped.
+    pub(crate) fn queue_turn(&mut self, heading: Heading)
+    {
+        let direction = match self.queued_turns.back()
+        {
+            Some(last) => Direction::from_heading(*last),
+            None => *self.snake.get_head().get_direction(),
+        };
+
+        let is_useless = Some(heading) == direction.to_heading()
+            || Some(heading) == direction.opposite().to_heading();
+        if is_useless || self.queued_turns.len() >= MAX_QUEUED_TURNS
+        {
+            return;
+        }
+
+        self.queued_turns.push_back(heading);
+    }
+
+    pub(crate) fn take_turn(&mut self) -> Option<Heading>
+    {
+        self.queued_turns.pop_front()
+    }
+
+    pub(crate) fn clear_turns(&mut self)
+    {
+        self.queued_turns.clear();
+    }
+
+    pub(crate) fn get_effects(&self) -> &Effects
+    {
+        &self.effects
+    }
+
+    pub(crate) fn get_effects_mut(&mut self) -> &mut Effects
+    {
+        &mut self.effects
+    }
+
+    /// Lets a shield take a fatal hit instead of the snake. Returns whether one did.
+    pub(crate) fn absorb_hit(&mut self) -> bool
+    {
+        self.effects.use_up(PowerUp::Shield)
+    }
+
+    pub(crate) fn get_head_color(&self) -> Color
+    {
+        self.start.get_color()
+    }
+
+    pub(crate) fn get_points(&self) -> Points
+    {
+        self.points
+    }
+
+    pub(crate) fn add_points(&mut self, points: Points)
+    {
+        self.points += points;
+    }
+
+    pub(crate) fn take_points(&mut self, points: Points)
+    {
+        self.points = self.points.saturating_sub(points);
+    }
+
+    pub(crate) fn get_lives(&self) -> u32
+    {
+        self.lives
+    }
+
+    pub(crate) fn is_alive(&self) -> bool
+    {
+        self.lives > 0
+    }
+
+    /// Takes a life away and puts the snake back at its start if any are left.
+    pub(crate) fn lose_life(&mut self)
+    {
+        self.lives = self.lives.saturating_sub(1);
+        if self.is_alive()
+        {
+            self.respawn();
+        }
+        else
+        {
+            self.queued_turns.clear();
+            self.effects.clear();
+        }
+    }
+
+    /// Puts the snake back at its start at its starting length, keeping points and lives.
+    pub(crate) fn respawn(&mut self)
+    {
+        self.queued_turns.clear();
+        self.effects.clear();
+        self.snake = Snake::new(self.start.clone(), self.initial_cell_count, self.snake_color);
+    }
+}
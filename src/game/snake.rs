@@ -1,10 +1,11 @@
 use crate::game::cell::{Cell, Direction};
 use crate::game::cell::Direction::{DOWN, LEFT, RIGHT, UP};
 
-use minifb::Key;
-use crate::game::{Color, Input};
-
+use crate::game::Color;
+use crate::game::command::Heading;
+use crate::game::food::Food;
 
+#[derive(Clone)]
 pub struct Snake
 {
     cells: Vec<Cell>,
@@ -12,31 +13,19 @@ pub struct Snake
 
 impl Snake
 {
-    pub fn new
-    (
-        screen_width: i64,
-        screen_height: i64,
-        initial_cell_count: i64,
-        cell_size: i64,
-        head_color: Color,
-        snake_color: Color,
-    ) -> Snake
+    /// Builds a snake behind `head`, trailing away from the direction it faces.
+    pub fn new(head: Cell, initial_cell_count: i64, snake_color: Color) -> Snake
     {
-        let mut cells = Vec::new();
-        for i in 0..initial_cell_count
-        {
-            let left = screen_width / 2;
-            let top = screen_height / 2 - (initial_cell_count - 1 - i) * cell_size;
-            let color = if i == 0
-            {
-                head_color
-            }
-            else
-            {
-                snake_color
-            };
+        let direction = *head.get_direction();
+        let mut cells = vec![head];
 
-            let cell = Cell::new(left, top, cell_size, UP, screen_width, screen_height, color);
+        for _ in 1..initial_cell_count
+        {
+            let mut cell = cells[cells.len() - 1].clone();
+            cell.set_direction(direction.opposite());
+            cell.step();
+            cell.set_direction(direction);
+            cell.set_color(snake_color);
             cells.push(cell);
         }
 
@@ -51,7 +40,7 @@ impl Snake
 
             if i < self.cells.len() - 1
             {
-                let direction = self.cells[i].get_direction().clone();
+                let direction = *self.cells[i].get_direction();
                 self.cells[i + 1].set_direction(direction);
             }
         }
@@ -64,7 +53,7 @@ impl Snake
         let head_direction = head.get_direction();
         match (head_direction, new_direction)
         {
-            (DOWN, UP) | (UP, DOWN) | (LEFT, RIGHT) | (RIGHT, LEFT) => return,
+            (DOWN, UP) | (UP, DOWN) | (LEFT, RIGHT) | (RIGHT, LEFT) => {},
             (_, new_dir) => head.set_direction(new_dir),
         }
     }
@@ -74,6 +63,30 @@ impl Snake
         &self.cells
     }
 
+    pub(crate) fn get_head(&self) -> &Cell
+    {
+        &self.cells[0]
+    }
+
+    /// Moves every cell that has just stepped onto a portal to the other end of it.
+    pub(crate) fn use_portals(&mut self, portals: &[(Cell, Cell)])
+    {
+        for cell in &mut self.cells
+        {
+            let exit = portals.iter().find_map(|(a, b)|
+            {
+                if cell.overlap(a) { Some(b) }
+                else if cell.overlap(b) { Some(a) }
+                else { None }
+            });
+
+            if let Some(exit) = exit
+            {
+                cell.move_to(exit);
+            }
+        }
+    }
+
     pub(crate) fn is_tangled(&self) -> bool
     {
         let head = &self.cells[0];
@@ -85,56 +98,58 @@ impl Snake
         false
     }
 
-    pub(crate) fn change_head(&mut self, mut new_head: Cell)
+    /// Returns the index of the food the head has landed on, if any.
+    pub(crate) fn get_collected_food(&self, foods: &[Food]) -> Option<usize>
     {
-        let snake_color = self.cells[1].get_color();
-        let head = &mut self.cells[0];
-        let head_direction = head.get_direction().clone();
-        let head_color = head.get_color();
-
-        head.set_color(snake_color);
-
-        new_head.set_direction(head_direction);
-        new_head.set_color(head_color);
+        let head = &self.cells[0];
+        foods.iter().position(|food| head.overlap(food.get_cell()))
+    }
 
-        let mut new_cells = vec![new_head];
-        new_cells.append(&mut self.cells);
+    /// Adds `tail` behind the last cell, which should be the cell the tail just left,
+    /// unless the snake has already grown back into it.
+    pub(crate) fn grow(&mut self, mut tail: Cell)
+    {
+        if self.cells[self.cells.len() - 1].overlap(&tail)
+        {
+            return;
+        }
 
-        self.cells = new_cells;
+        let color = self.cells[self.cells.len() - 1].get_color();
+        tail.set_color(color);
+        self.cells.push(tail);
     }
 
-    pub(crate) fn is_collecting_point(&self, point_cell: &Cell) -> bool
+    /// Cuts the snake off where the head ran into its body, keeping the head and one body cell.
+    pub(crate) fn untangle(&mut self)
     {
-        // Checking for the next head position.
-
         let head = &self.cells[0];
-        let mut new_head = head.clone();
-        new_head.step();
-        if new_head.overlap(point_cell)
+        if let Some(hit) = self.cells.iter().skip(1).position(|cell| cell.overlap(head))
         {
-            return true;
+            self.cells.truncate((hit + 1).max(2));
         }
-        false
     }
 
-    pub fn go(&mut self, input: Option<Input>)
+    /// Removes up to `count` cells from the tail, always keeping the head and one body cell.
+    pub(crate) fn shrink(&mut self, count: usize)
+    {
+        let new_len = self.cells.len().saturating_sub(count).max(2);
+        self.cells.truncate(new_len);
+    }
+
+    /// Turns the head without moving, for when it cannot go on the way it faces.
+    pub(crate) fn turn_to(&mut self, heading: Heading)
+    {
+        self.turn(Direction::from_heading(heading));
+    }
+
+    pub fn go(&mut self, heading: Option<Heading>)
     {
         self.step();
 
         // Check for turning the snake.
-        let new_direction = match input
-        {
-            // For example.
-            Some(Key::Up) => Some(UP),
-            Some(Key::Down) => Some(DOWN),
-            Some(Key::Left) => Some(LEFT),
-            Some(Key::Right) => Some(RIGHT),
-            _ => None,
-        };
-
-        if let Some(dir) = new_direction
+        if let Some(heading) = heading
         {
-            self.turn(dir);
+            self.turn(Direction::from_heading(heading));
         }
     }
 }
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use crate::game::Points;
+
+/// How the speed grows with the best score.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SpeedCurve
+{
+    /// `rate` ticks per second more for every point.
+    Linear,
+    /// `rate` ticks per second more for every point squared.
+    Quadratic,
+    /// `rate` ticks per second more for every `points_per_level` points.
+    Stepped,
+    /// Fast at first and levelling off, `rate` times the natural logarithm of one more than the points.
+    Logarithmic,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub(crate) struct SpeedConfig
+{
+    /// Ticks per second at the start of a game.
+    pub(crate) start: f64,
+    pub(crate) curve: SpeedCurve,
+    pub(crate) rate: f64,
+    /// Points that make up a level on the stepped curve.
+    pub(crate) points_per_level: Points,
+    /// Shortest time between two ticks, in milliseconds, however many points there are.
+    pub(crate) min_interval: u64,
+}
+
+impl Default for SpeedConfig
+{
+    /// Starts at 12.5 ticks per second and speeds up quadratically to at most 50.
+    fn default() -> SpeedConfig
+    {
+        SpeedConfig
+        {
+            start: 12.5,
+            curve: SpeedCurve::Quadratic,
+            rate: 0.0003,
+            points_per_level: 10,
+            min_interval: 20,
+        }
+    }
+}
+
+impl SpeedConfig
+{
+    /// Ticks per second once the best player has `points` points.
+    pub(crate) fn ticks_per_second(&self, points: Points) -> f64
+    {
+        let x = points as f64;
+        let increase = match self.curve
+        {
+            SpeedCurve::Linear => x,
+            SpeedCurve::Quadratic => x * x,
+            SpeedCurve::Stepped => (points / self.points_per_level.max(1)) as f64,
+            SpeedCurve::Logarithmic => x.ln_1p(),
+        };
+
+        let max_ticks_per_second = 1000.0 / self.min_interval.max(1) as f64;
+        (self.start + self.rate * increase).min(max_ticks_per_second)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn speed(curve: SpeedCurve) -> SpeedConfig
+    {
+        SpeedConfig { start: 10.0, curve, rate: 0.5, points_per_level: 4, min_interval: 1 }
+    }
+
+    fn assert_close(actual: f64, expected: f64)
+    {
+        assert!((actual - expected).abs() < 1e-9, "{actual} is not {expected}");
+    }
+
+    #[test]
+    fn every_curve_starts_at_the_start_speed()
+    {
+        for curve in [SpeedCurve::Linear, SpeedCurve::Quadratic, SpeedCurve::Stepped,
+                      SpeedCurve::Logarithmic]
+        {
+            assert_close(speed(curve).ticks_per_second(0), 10.0);
+        }
+    }
+
+    #[test]
+    fn curves_speed_up_by_their_own_measure_of_the_points()
+    {
+        assert_close(speed(SpeedCurve::Linear).ticks_per_second(6), 13.0);
+        assert_close(speed(SpeedCurve::Quadratic).ticks_per_second(6), 28.0);
+        assert_close(speed(SpeedCurve::Stepped).ticks_per_second(7), 10.5);
+        assert_close(speed(SpeedCurve::Stepped).ticks_per_second(8), 11.0);
+        assert_close(speed(SpeedCurve::Logarithmic).ticks_per_second(6), 10.0 + 0.5 * 7f64.ln());
+    }
+
+    #[test]
+    fn the_minimum_interval_caps_the_speed()
+    {
+        let config = SpeedConfig { min_interval: 50, ..speed(SpeedCurve::Quadratic) };
+        assert_close(config.ticks_per_second(4), 18.0);
+        assert_close(config.ticks_per_second(1000), 20.0);
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// How a pair of opposite screen edges is glued together.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum EdgeMode
+{
+    /// Leave on one side, come back at the same spot on the opposite one.
+    #[default]
+    Wrap,
+    /// Leave on one side, come back on the opposite one mirrored along the edge.
+    /// The snake keeps moving through the seam in the same direction,
+    /// only its position along the seam is flipped.
+    Mirrored,
+    /// Touching the edge ends the game.
+    Solid,
+}
+
+/// Edge modes of the board. Two wrapped pairs make a torus, one mirrored pair
+/// a Klein bottle and two mirrored pairs a projective plane.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub(crate) struct Topology
+{
+    #[serde(default)]
+    pub(crate) left_right: EdgeMode,
+    #[serde(default)]
+    pub(crate) top_bottom: EdgeMode,
+}
@@ -0,0 +1,215 @@
+use std::fs::File;
+use std::io::{ErrorKind, Read, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::config::Config;
+use crate::game::Points;
+
+/// One finished run on the high score table.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HighScore
+{
+    pub(crate) name: String,
+    pub(crate) score: Points,
+    /// Cells in the snake when the run ended.
+    pub(crate) length: usize,
+    /// Time spent playing, in seconds, not counting pauses.
+    pub(crate) duration: u64,
+    /// Day the run was played, as `YYYY-MM-DD`.
+    pub(crate) date: String,
+    /// Config the run was played with, seed included.
+    pub(crate) config: Config,
+}
+
+impl HighScore
+{
+    pub(crate) fn new(name: &str, score: Points, length: usize, duration: Duration, config: &Config,
+                      seed: u64) -> HighScore
+    {
+        let mut config = config.clone();
+        config.seed = Some(seed);
+
+        HighScore
+        {
+            name: name.to_string(),
+            score,
+            length,
+            duration: duration.as_secs(),
+            date: today(),
+            config,
+        }
+    }
+}
+
+/// The best runs, highest score first, kept in a local file between games.
+#[derive(Serialize, Deserialize, Default)]
+pub struct HighScores
+{
+    entries: Vec<HighScore>,
+    #[serde(skip)]
+    filename: String,
+    #[serde(skip)]
+    count: usize,
+}
+
+impl HighScores
+{
+    /// Loads the table from `filename`, starting an empty one if the file does not exist yet.
+    /// Only the best `count` runs are kept.
+    pub fn load(filename: &str, count: usize) -> Result<HighScores, String>
+    {
+        let mut high_scores = match File::open(filename)
+        {
+            Ok(mut file) =>
+            {
+                let mut content = String::new();
+                match file.read_to_string(&mut content)
+                {
+                    Ok(_) => {},
+                    Err(_) => return Err("Could not read the high score file".to_string()),
+                }
+
+                match serde_json::from_str(&content)
+                {
+                    Ok(h) => h,
+                    Err(e) => return Err(e.to_string()),
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => HighScores::default(),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        high_scores.filename = filename.to_string();
+        high_scores.count = count;
+        high_scores.entries.truncate(count);
+
+        Ok(high_scores)
+    }
+
+    fn save(&self) -> Result<(), String>
+    {
+        let content = match serde_json::to_string_pretty(self)
+        {
+            Ok(c) => c,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let mut file = match File::create(&self.filename)
+        {
+            Ok(f) => f,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        match file.write_all(content.as_bytes())
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    pub(crate) fn get_entries(&self) -> &Vec<HighScore>
+    {
+        &self.entries
+    }
+
+    /// Whether a run with `score` points makes it onto the table.
+    pub(crate) fn qualifies(&self, score: Points) -> bool
+    {
+        if score == 0 || self.count == 0
+        {
+            return false;
+        }
+
+        match self.entries.get(self.count - 1)
+        {
+            Some(last) => score > last.score,
+            None => true,
+        }
+    }
+
+    /// Puts a run in its place on the table and saves the table.
+    /// Runs with the same score keep the order they were played in.
+    pub(crate) fn insert(&mut self, entry: HighScore) -> Result<(), String>
+    {
+        let place = self.entries.iter()
+            .position(|e| e.score < entry.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(place, entry);
+        self.entries.truncate(self.count);
+
+        self.save()
+    }
+}
+
+/// Today's date in UTC as `YYYY-MM-DD`.
+fn today() -> String
+{
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    // Days since 1970-01-01 to a civil date, counting in 400 year eras that start on March 1st.
+    let days = (seconds / 86400) as i64 + 719468;
+    let era = days / 146097;
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// An empty table of `count` runs, kept in a fresh file named after `name`.
+    fn empty_table(name: &str, count: usize) -> HighScores
+    {
+        let path = std::env::temp_dir().join(format!("supersnake_{name}_high_scores.json"));
+        let _ = std::fs::remove_file(&path);
+        HighScores::load(&path.display().to_string(), count).unwrap()
+    }
+
+    fn entry(name: &str, score: Points) -> HighScore
+    {
+        HighScore::new(name, score, 3, Duration::from_secs(60), &Config::for_board(8, 8), 0)
+    }
+
+    fn names(high_scores: &HighScores) -> Vec<&str>
+    {
+        high_scores.get_entries().iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn runs_are_kept_best_first_and_ties_in_the_order_played()
+    {
+        let mut high_scores = empty_table("ordering", 3);
+        for (name, score) in [("a", 10), ("b", 30), ("c", 20), ("d", 20)]
+        {
+            high_scores.insert(entry(name, score)).unwrap();
+        }
+        assert_eq!(names(&high_scores), vec!["b", "c", "d"]);
+
+        let reloaded = HighScores::load(&high_scores.filename, 3).unwrap();
+        assert_eq!(names(&reloaded), vec!["b", "c", "d"]);
+    }
+
+    #[test]
+    fn a_run_qualifies_by_beating_the_last_place_of_a_full_table()
+    {
+        let mut high_scores = empty_table("qualifying", 2);
+        assert!(!high_scores.qualifies(0));
+        assert!(high_scores.qualifies(1));
+
+        high_scores.insert(entry("a", 30)).unwrap();
+        high_scores.insert(entry("b", 20)).unwrap();
+        assert!(!high_scores.qualifies(20));
+        assert!(high_scores.qualifies(21));
+    }
+}
@@ -1,17 +1,68 @@
 #![windows_subsystem = "windows"] // hide the console window
 
+mod args;
+use crate::args::Args;
 mod config;
 use crate::config::Config;
 mod game;
 use crate::game::Game;
 mod display;
-use crate::display::Display;
+use crate::display::{window_title, Display};
+use crate::display::clock::SystemClock;
+use crate::display::event_log::EventLog;
 use crate::display::screen::Screen;
 use crate::display::sound::Sound;
+mod replay;
+use crate::replay::Replay;
+mod highscores;
+use crate::highscores::HighScores;
+mod campaign;
+use crate::campaign::Campaign;
 
 fn main()
 {
-    let config = match Config::new("config.json")
+    let args = match Args::parse()
+    {
+        Ok(a) => a,
+        Err(msg) =>
+        {
+            eprintln!("{msg}");
+            return;
+        }
+    };
+
+    if let Some(filename) = &args.verify
+    {
+        match Replay::load(filename).and_then(|replay| replay.verify())
+        {
+            Ok(points) => println!("Replay verified: {points} points."),
+            Err(msg) => eprintln!("{msg}"),
+        }
+        return;
+    }
+
+    let playback = match &args.replay
+    {
+        Some(filename) => match Replay::load(filename)
+        {
+            Ok(r) => Some(r),
+            Err(msg) =>
+            {
+                eprintln!("Could not load the replay. {msg}");
+                return;
+            }
+        },
+        None => None,
+    };
+
+    // A replay brings its own config, so the run is played back exactly as recorded.
+    let config = match &playback
+    {
+        Some(replay) => Ok(replay.get_config().clone()),
+        None => Config::new("config.json"),
+    };
+
+    let mut config = match config
     {
         Ok(c) => c,
         Err(_) =>
@@ -21,6 +72,38 @@ fn main()
         }
     };
 
+    if args.seed.is_some()
+    {
+        config.seed = args.seed;
+    }
+    if args.autopilot
+    {
+        config.autopilot = true;
+    }
+    if args.campaign.is_some()
+    {
+        config.campaign = args.campaign.clone();
+    }
+
+    // A campaign plays its current level, built on top of the config.
+    let campaign = match (&config.campaign, &playback)
+    {
+        (Some(filename), None) => match Campaign::load(filename, &config)
+        {
+            Ok(c) => Some(c),
+            Err(msg) =>
+            {
+                eprintln!("Could not load the campaign. {msg}");
+                return;
+            }
+        },
+        _ => None,
+    };
+    if let Some(campaign) = &campaign
+    {
+        config = campaign.make_config();
+    }
+
     let mut game = match Game::new(&config)
     {
         Ok(g) => g,
@@ -31,7 +114,10 @@ fn main()
         }
     };
     
-    let screen = match Screen::new("Super snake", &config)
+    let seed = game.get_seed();
+    println!("Seed: {seed}");
+
+    let screen = match Screen::new(&window_title(seed), &config)
     {
         Ok(s) => s,
         Err(msg) =>
@@ -41,7 +127,7 @@ fn main()
         }
     };
 
-    let sound = match Sound::new(&"assets/music".to_string())
+    let mut sound = match Sound::new(config.music_volume, &config.sound_effects)
     {
         Ok(s) => Some(s),
         Err(msg) => 
@@ -51,7 +137,42 @@ fn main()
         }
     };
 
-    let mut display = Display::new(&mut game, screen, sound);
+    if let Some(Err(msg)) = sound.as_mut().map(|s| s.load_music(&config.music_path))
+    {
+        eprintln!("Could not load the music. {msg}");
+    }
+
+    let recording = args.record.as_ref()
+        .map(|filename| (Replay::new(&config, seed), filename));
+
+    let effects_player = sound.as_ref().map(|s| s.make_effects_player());
+    let mut display = Display::new(&mut game, &config, screen, sound,
+                                   Box::new(SystemClock::new()));
+
+    if let Some((replay, filename)) = recording
+    {
+        display.record(replay, filename);
+    }
+    if let Some(effects_player) = effects_player
+    {
+        display.subscribe(Box::new(effects_player));
+    }
+    if args.log_events
+    {
+        display.subscribe(Box::new(EventLog));
+    }
+    // Played back runs are not new runs, and campaign levels are scored by their goals,
+    // so neither goes on the high score table.
+    match (playback, campaign)
+    {
+        (Some(replay), _) => display.play_back(replay),
+        (None, Some(campaign)) => display.play_campaign(campaign),
+        (None, None) => match HighScores::load(&config.get_high_score_file(), config.high_score_count)
+        {
+            Ok(h) => display.keep_high_scores(h),
+            Err(msg) => eprintln!("Could not load the high scores. {msg}"),
+        },
+    }
 
     match display.run()
     {
@@ -59,7 +180,6 @@ fn main()
         Err(msg) =>
         {
             eprintln!("{msg}");
-            return;
         }
     }
 }
@@ -0,0 +1,202 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use serde::{Deserialize, Serialize};
+use crate::config::Config;
+use crate::game::Game;
+use crate::game::command::Command;
+
+/// Bumped whenever the layout of the replay file or the meaning of its inputs changes.
+const REPLAY_VERSION: u32 = 5;
+
+/// A recorded run: the config and seed the game was started with
+/// and the commands passed to `Game::go` on every tick.
+#[derive(Serialize, Deserialize)]
+pub struct Replay
+{
+    version: u32,
+    seed: u64,
+    config: Config,
+    inputs: Vec<Vec<Command>>,
+    points: u64,
+}
+
+impl Replay
+{
+    pub fn new(config: &Config, seed: u64) -> Replay
+    {
+        let mut config = config.clone();
+        config.seed = Some(seed);
+
+        Replay
+        {
+            version: REPLAY_VERSION,
+            seed,
+            config,
+            inputs: vec![],
+            points: 0,
+        }
+    }
+
+    pub fn load(filename: &str) -> Result<Replay, String>
+    {
+        let mut file = match File::open(filename)
+        {
+            Ok(f) => f,
+            Err(e) => return Err(e.to_string()),
+        };
+        let mut content = String::new();
+
+        match file.read_to_string(&mut content)
+        {
+            Ok(_) => {},
+            Err(_) => return Err("Could not read the replay file".to_string()),
+        }
+
+        let replay: Replay = match serde_json::from_str(&content)
+        {
+            Ok(r) => r,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        if replay.version != REPLAY_VERSION
+        {
+            let err_msg = format!("Unsupported replay version {} (expected {}).",
+                                  replay.version, REPLAY_VERSION);
+            return Err(err_msg);
+        }
+
+        Ok(replay)
+    }
+
+    pub fn save(&self, filename: &str) -> Result<(), String>
+    {
+        let content = match serde_json::to_string(self)
+        {
+            Ok(c) => c,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let mut file = match File::create(filename)
+        {
+            Ok(f) => f,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        match file.write_all(content.as_bytes())
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    pub fn get_config(&self) -> &Config
+    {
+        &self.config
+    }
+
+    pub(crate) fn get_input(&self, tick: usize) -> Option<Vec<Command>>
+    {
+        self.inputs.get(tick).cloned()
+    }
+
+    pub(crate) fn record(&mut self, input: Vec<Command>)
+    {
+        self.inputs.push(input);
+    }
+
+    pub fn set_points(&mut self, points: u64)
+    {
+        self.points = points;
+    }
+
+    /// Plays the recorded inputs back without a window and checks
+    /// that they lead to the score stored in the file.
+    pub fn verify(&self) -> Result<u64, String>
+    {
+        let mut game = Game::new(&self.config)?;
+        for input in &self.inputs
+        {
+            if !game.is_running()
+            {
+                break;
+            }
+            game.go(input);
+        }
+
+        let points = game.get_points();
+        if points != self.points
+        {
+            let err_msg = format!("Replay claims {} points, but playing it back gives {}.",
+                                  self.points, points);
+            return Err(err_msg);
+        }
+
+        Ok(points)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::game::command::Heading;
+
+    /// Plays a short autopilot run and records it, as the display does.
+    fn record_run() -> Replay
+    {
+        let mut config = Config::for_board(12, 12);
+        config.autopilot = true;
+        let mut replay = Replay::new(&config, 7);
+        let mut game = Game::new(replay.get_config()).unwrap();
+        for tick in 0..100
+        {
+            let input = match tick % 10
+            {
+                0 => vec![Command::Turn(0, Heading::Left)],
+                _ => vec![],
+            };
+            game.go(&input);
+            replay.record(input);
+        }
+        replay.set_points(game.get_points());
+        replay
+    }
+
+    fn save_and_load(replay: &Replay, name: &str) -> Result<Replay, String>
+    {
+        let path = std::env::temp_dir().join(format!("supersnake_{name}.replay"));
+        let filename = path.display().to_string();
+        replay.save(&filename)?;
+        Replay::load(&filename)
+    }
+
+    #[test]
+    fn saved_replays_load_and_verify()
+    {
+        let replay = record_run();
+        assert!(replay.points > 0);
+
+        let loaded = save_and_load(&replay, "round_trip").unwrap();
+        assert_eq!(loaded.inputs, replay.inputs);
+        assert_eq!(loaded.verify(), Ok(replay.points));
+    }
+
+    #[test]
+    fn replays_claiming_other_points_fail_to_verify()
+    {
+        let mut replay = record_run();
+        replay.set_points(replay.points + 1);
+
+        let loaded = save_and_load(&replay, "wrong_points").unwrap();
+        assert!(loaded.verify().is_err());
+    }
+
+    #[test]
+    fn replays_of_another_version_do_not_load()
+    {
+        let mut replay = record_run();
+        replay.version = REPLAY_VERSION + 1;
+
+        assert!(save_and_load(&replay, "old_version").is_err());
+    }
+}